@@ -23,7 +23,11 @@ export enum TagType {
   Refillable1Of1,
   WalletRestrictedFungible,
   HotPotato,
-  ProgrammableUnique
+  ProgrammableUnique,
+  Bundle,
+  TradeIn,
+  CheckIn,
+  Raffle
 }
 
 export interface AnchorTagType {
@@ -34,6 +38,23 @@ export interface AnchorTagType {
   walletRestrictedFungible?: boolean;
   hotPotato?: boolean;
   programmableUnique?: boolean;
+  bundle?: boolean;
+  tradeIn?: boolean;
+  checkIn?: boolean;
+  raffle?: boolean;
+}
+
+export interface AnchorGateAction {
+  hold?: {};
+  lock?: {};
+  burn?: {};
+}
+
+export interface TagGate {
+  mint: PublicKey;
+  isCollection: boolean;
+  amount: BN;
+  action: AnchorGateAction;
 }
 
 export interface Config {
@@ -56,6 +77,22 @@ export interface Tag {
   whitelistBurn: PublicKey;
   bump: Number;
   currentTokenLocation: PublicKey;
+  quest: PublicKey | null;
+  campaign: PublicKey | null;
+  season: number;
+  additionalAuthorities: PublicKey[];
+  programAuthority: PublicKey | null;
+  sponsorshipLimit: BN;
+  referralReward: BN;
+  referralRewardMint: PublicKey | null;
+}
+
+export interface Quest {
+  id: BN;
+}
+
+export interface Campaign {
+  id: BN;
 }
 
 export interface UserInfo {
@@ -77,6 +114,24 @@ export interface AddOrRefillTagParams {
   // candy only
   pricePerMint?: BN | null;
   whitelistBurn?: boolean;
+  // trade in only
+  inputIsCollection?: boolean;
+  inputAmount?: BN;
+  inputBurn?: boolean;
+  // fungible only
+  vestingCliff?: BN;
+  vestingDuration?: BN;
+  // any type
+  claimPeriod?: BN;
+  perPeriod?: BN;
+  gate?: TagGate | null;
+  sponsorshipLimit?: BN;
+  escrowDuration?: BN;
+  // raffle only
+  raffleEndsAt?: BN;
+  rafflePrize?: BN;
+  // existing tags only
+  newSeason?: boolean;
 }
 
 export interface AddOrRefillTagAccounts {
@@ -88,11 +143,18 @@ export interface AddOrRefillTagAccounts {
   candyMachine?: PublicKey;
   whitelistMint?: PublicKey;
   paymentTokenMint?: PublicKey;
+  inputMint?: PublicKey;
+  template?: PublicKey;
 }
 
 export interface ClaimTagParams {
   creatorBump?: number;
   minterPays?: boolean;
+  allowlistProof?: number[][];
+  allocation?: BN;
+  maxPrice?: BN;
+  identityHash?: number[];
+  comboExpiresAt?: BN;
 }
 
 export interface ClaimTagAccounts {
@@ -112,11 +174,14 @@ export interface ClaimTagAccounts {
   collectionMasterEdition?: PublicKey;
   collectionAuthorityRecord?: PublicKey;
   candyMachineAuthority?: PublicKey;
+  referrer?: PublicKey;
 }
 
 export interface ClaimTagAdditionalArgs {
   tag: Tag;
   config: Config;
+  quest?: Quest;
+  campaign?: Campaign;
   nextEdition?: BN;
   createAta: boolean;
   candyProgram?: Program;
@@ -300,6 +365,8 @@ export class CupcakeProgram {
     const addArgs: ClaimTagAdditionalArgs = {
       tag,
       config: (await this.program.account.config.fetch(tag.config)) as Config,
+      quest: tag.quest ? ((await this.program.account.quest.fetch(tag.quest)) as Quest) : undefined,
+      campaign: tag.campaign ? ((await this.program.account.campaign.fetch(tag.campaign)) as Campaign) : undefined,
       createAta,
       nextEdition,
       candyProgram,
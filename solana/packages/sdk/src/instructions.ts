@@ -1,10 +1,10 @@
 import { Metadata } from "@metaplex-foundation/mpl-token-metadata";
 import * as TokenAuth from "@metaplex-foundation/mpl-token-auth-rules"
-import { Program, Provider } from "@project-serum/anchor";
-import { ASSOCIATED_TOKEN_PROGRAM_ID, createAssociatedTokenAccountInstruction, createInitializeMintInstruction, createMintToInstruction, getAssociatedTokenAddressSync, MintLayout, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { BN, Program, Provider } from "@project-serum/anchor";
+import { ASSOCIATED_TOKEN_PROGRAM_ID, createAssociatedTokenAccountInstruction, createInitializeMintInstruction, createMintToInstruction, getAssociatedTokenAddressSync, MintLayout, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { Keypair, PublicKey, SystemProgram, SYSVAR_CLOCK_PUBKEY, SYSVAR_INSTRUCTIONS_PUBKEY, SYSVAR_RENT_PUBKEY, SYSVAR_SLOT_HASHES_PUBKEY } from "@solana/web3.js";
 import { TOKEN_METADATA_PROGRAM_ID, AddOrRefillTagAccounts, AddOrRefillTagParams, ClaimTagAccounts, ClaimTagAdditionalArgs, ClaimTagParams, InitializeAccounts } from "./cupcake_program";
-import { getConfig, getTag, getTokenRecordPDA, getUserHotPotatoToken, getUserInfo } from "./pda";
+import { getCampaignUserInfo, getConfig, getQuestProgress, getReferralInfo, getReferrerInfo, getSessionKey, getTag, getTokenRecordPDA, getTreasury, getUserHotPotatoToken, getUserInfo } from "./pda";
 import { CANDY_MACHINE_ADDRESS, getCandyMachineCreator, getCollectionPDA, getEditionMarkPda, getMasterEdition, getMetadata } from "./utils/mpl";
 
 export class CupcakeInstruction {
@@ -133,6 +133,15 @@ export class CupcakeInstruction {
         remainingAccounts.push({ pubkey: TOKEN_METADATA_PROGRAM_ID, isWritable: false, isSigner: false });
         remainingAccounts.push({ pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isWritable: false, isSigner: false });
       }
+    } else if (args.tagType.tradeIn || args.tagType.raffle) {
+      const configTokenAta = getAssociatedTokenAddressSync(accounts.tokenMint, authority);
+
+      remainingAccounts.push({ pubkey: accounts.tokenMint, isWritable: false, isSigner: false });
+      remainingAccounts.push({ pubkey: configTokenAta, isWritable: true, isSigner: false });
+
+      if (args.tagType.tradeIn) {
+        remainingAccounts.push({ pubkey: accounts.inputMint, isWritable: false, isSigner: false });
+      }
     } else if (args.tagType.hotPotato) {
       const configTokenAta = getAssociatedTokenAddressSync(accounts.tokenMint, authority);
 
@@ -185,7 +194,28 @@ export class CupcakeInstruction {
         {
           instructions: [
             await this.program.methods
-              .addOrRefillTag(args)
+              .addOrRefillTag({
+                uid: args.uid,
+                tagType: args.tagType,
+                numClaims: args.numClaims,
+                perUser: args.perUser,
+                minterPays: args.minterPays,
+                pricePerMint: args.pricePerMint || null,
+                whitelistBurn: args.whitelistBurn || false,
+                inputIsCollection: args.inputIsCollection || false,
+                inputAmount: args.inputAmount || new BN(0),
+                inputBurn: args.inputBurn || false,
+                vestingCliff: args.vestingCliff || new BN(0),
+                vestingDuration: args.vestingDuration || new BN(0),
+                claimPeriod: args.claimPeriod || new BN(0),
+                perPeriod: args.perPeriod || new BN(0),
+                gate: args.gate || null,
+                sponsorshipLimit: args.sponsorshipLimit || new BN(0),
+                escrowDuration: args.escrowDuration || new BN(0),
+                raffleEndsAt: args.raffleEndsAt || new BN(0),
+                rafflePrize: args.rafflePrize || new BN(0),
+                newSeason: args.newSeason || false,
+              })
               .accounts({
                 authority,
                 config,
@@ -195,6 +225,8 @@ export class CupcakeInstruction {
                 systemProgram: SystemProgram.programId,
                 tokenProgram: TOKEN_PROGRAM_ID,
                 rent: SYSVAR_RENT_PUBKEY,
+                // Anchor reads the optional template by position, with the program id standing in for a missing one.
+                template: accounts.template || this.program.programId,
               })
               .remainingAccounts(remainingAccounts)
               .instruction(),
@@ -269,7 +301,12 @@ export class CupcakeInstruction {
 
     const remainingAccounts = [];
 
-    const configTokenAta = getAssociatedTokenAddressSync(tagObj.tokenMint, configObj.authority);
+    // Token-2022 Sprinkles transfer through the Token-2022 program, which also owns their ATAs.
+    const tokenMintInfo = await this.program.provider.connection.getAccountInfo(tagObj.tokenMint);
+    const isToken2022 = !!tokenMintInfo && tokenMintInfo.owner.equals(TOKEN_2022_PROGRAM_ID);
+    const tokenProgramId = isToken2022 ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
+
+    const configTokenAta = getAssociatedTokenAddressSync(tagObj.tokenMint, configObj.authority, false, tokenProgramId);
 
     const newTokenMintKeypair = Keypair.generate();
     const newTokenInfo = {
@@ -280,7 +317,7 @@ export class CupcakeInstruction {
 
     // default is a normal token transfer of some kind, but if candy or edition,
     // reset to new token mint
-    let userAta = getAssociatedTokenAddressSync(tagObj.tokenMint, user);
+    let userAta = getAssociatedTokenAddressSync(tagObj.tokenMint, user, false, tokenProgramId);
 
     if (tagObj.tagType.limitedOrOpenEdition || tagObj.tagType.candyMachineDrop) {
       newTokenInfo.newMetadata = await getMetadata(newTokenInfo.newTokenMint);
@@ -309,7 +346,9 @@ export class CupcakeInstruction {
         );
       }
     } else if (additionalArgs.createAta) {
-      priorInstructions.push(createAssociatedTokenAccountInstruction(payer, userAta, user, tagObj.tokenMint));
+      priorInstructions.push(
+        createAssociatedTokenAccountInstruction(payer, userAta, user, tagObj.tokenMint, tokenProgramId)
+      );
     }
 
     if (tagObj.tagType.walletRestrictedFungible || tagObj.tagType.refillable1Of1 || tagObj.tagType.singleUse1Of1 || tagObj.tagType.programmableUnique) {
@@ -460,22 +499,99 @@ export class CupcakeInstruction {
       );
     }
 
+    // Anchor reads the optional accounts of claimTagV2 by position, with the program id standing in for a missing one.
+    const missing = this.program.programId;
+    const optionalAccounts = {
+      quest: missing,
+      questProgress: missing,
+      campaign: missing,
+      campaignUserInfo: missing,
+      sessionKey: missing,
+      treasury: missing,
+      associatedTokenProgram: missing,
+      tokenMint: missing,
+      token2022Program: missing,
+      referrer: missing,
+      referrerInfo: missing,
+      treasuryToken: missing,
+      referrerToken: missing,
+      referralInfo: missing,
+    };
+
+    if (isToken2022) {
+      optionalAccounts.tokenMint = tagObj.tokenMint;
+      optionalAccounts.token2022Program = TOKEN_2022_PROGRAM_ID;
+    }
+
+    if (tagObj.quest && additionalArgs.quest) {
+      optionalAccounts.quest = tagObj.quest;
+      optionalAccounts.questProgress = (
+        await getQuestProgress(this.program, additionalArgs.quest.id, configObj.authority, user)
+      )[0];
+    }
+
+    if (tagObj.campaign && additionalArgs.campaign) {
+      optionalAccounts.campaign = tagObj.campaign;
+      optionalAccounts.campaignUserInfo = (
+        await getCampaignUserInfo(this.program, additionalArgs.campaign.id, configObj.authority, user)
+      )[0];
+    }
+
+    const isTagAuthority =
+      tagObj.tagAuthority.equals(tagAuthority) ||
+      (tagObj.additionalAuthorities || []).some((authority) => authority.equals(tagAuthority)) ||
+      (tagObj.programAuthority && tagObj.programAuthority.equals(tagAuthority));
+    if (!isTagAuthority) {
+      optionalAccounts.sessionKey = (await getSessionKey(this.program, configObj.authority, tagAuthority))[0];
+    }
+
+    const treasury = (await getTreasury(this.program, configObj.authority))[0];
+    const rewardsReferrals = tagObj.referralReward && !tagObj.referralReward.isZero();
+    if ((tagObj.sponsorshipLimit && !tagObj.sponsorshipLimit.isZero()) || rewardsReferrals) {
+      optionalAccounts.treasury = treasury;
+    }
+
+    if (accounts.referrer && rewardsReferrals) {
+      optionalAccounts.referrer = accounts.referrer;
+      optionalAccounts.referrerInfo = (
+        await getReferrerInfo(this.program, tagObj.uid, configObj.authority, accounts.referrer)
+      )[0];
+      optionalAccounts.referralInfo = (await getReferralInfo(this.program, tagObj.uid, configObj.authority, user))[0];
+
+      if (tagObj.referralRewardMint) {
+        optionalAccounts.treasuryToken = getAssociatedTokenAddressSync(tagObj.referralRewardMint, treasury, true);
+        optionalAccounts.referrerToken = getAssociatedTokenAddressSync(tagObj.referralRewardMint, accounts.referrer);
+      }
+    }
+
     const instruction = await this.program.methods
-      .claimTag(args.creatorBump)
+      .claimTagV2(args.creatorBump, {
+        allowlistProof: args.allowlistProof || [],
+        allocation: args.allocation || null,
+        maxPrice: args.maxPrice || null,
+        identityHash: args.identityHash || null,
+        comboExpiresAt: args.comboExpiresAt || null,
+      })
       .accounts({
         user,
         payer,
         config,
         tagAuthority,
         tag,
-        userInfo: (await getUserInfo(this.program, tagObj.uid, configObj.authority, user))[0],
+        userInfo: (await getUserInfo(this.program, tagObj.uid, configObj.authority, user, tagObj.season))[0],
         systemProgram: SystemProgram.programId,
         tokenProgram: TOKEN_PROGRAM_ID,
         rent: SYSVAR_RENT_PUBKEY,
+        ...optionalAccounts,
       })
       .remainingAccounts(remainingAccounts)
       .instruction();
 
+    // Claims to an identity write to its ClaimEscrow, which stands in as the user.
+    if (args.identityHash) {
+      instruction.keys.find((key) => key.pubkey.equals(user))!.isWritable = true;
+    }
+
     if (accounts.collectionMasterEdition) {
       return {
         transactions: [
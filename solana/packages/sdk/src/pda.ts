@@ -14,9 +14,59 @@ export const getTag = async (program: Program, tagUID: BN, authority: PublicKey)
   );
 };
 
-export const getUserInfo = async (program: Program, tagUID: BN, authority: PublicKey, user: PublicKey) => {
+export const getUserInfo = async (
+  program: Program,
+  tagUID: BN,
+  authority: PublicKey,
+  user: PublicKey,
+  season: number = 0
+) => {
+  // The first season has no season seed, so UserInfos created before seasons existed keep their addresses.
+  const seasonSeed = season === 0 ? Buffer.alloc(0) : new BN(season).toBuffer('le', 4);
+  return await PublicKey.findProgramAddress(
+    [Buffer.from(PREFIX), authority.toBuffer(), tagUID.toBuffer('le', 8), user.toBuffer(), seasonSeed],
+    program.programId
+  );
+};
+
+export const getQuestProgress = async (program: Program, questId: BN, authority: PublicKey, user: PublicKey) => {
+  return await PublicKey.findProgramAddress(
+    [Buffer.from(PREFIX), authority.toBuffer(), Buffer.from('quest'), questId.toBuffer('le', 8), user.toBuffer()],
+    program.programId
+  );
+};
+
+export const getCampaignUserInfo = async (program: Program, campaignId: BN, authority: PublicKey, user: PublicKey) => {
+  return await PublicKey.findProgramAddress(
+    [Buffer.from(PREFIX), authority.toBuffer(), Buffer.from('campaign'), campaignId.toBuffer('le', 8), user.toBuffer()],
+    program.programId
+  );
+};
+
+export const getSessionKey = async (program: Program, authority: PublicKey, sessionKey: PublicKey) => {
+  return await PublicKey.findProgramAddress(
+    [Buffer.from(PREFIX), authority.toBuffer(), Buffer.from('session'), sessionKey.toBuffer()],
+    program.programId
+  );
+};
+
+export const getTreasury = async (program: Program, authority: PublicKey) => {
+  return await PublicKey.findProgramAddress(
+    [Buffer.from(PREFIX), authority.toBuffer(), Buffer.from('treasury')],
+    program.programId
+  );
+};
+
+export const getReferrerInfo = async (program: Program, tagUID: BN, authority: PublicKey, referrer: PublicKey) => {
+  return await PublicKey.findProgramAddress(
+    [Buffer.from(PREFIX), authority.toBuffer(), tagUID.toBuffer('le', 8), Buffer.from('referral'), referrer.toBuffer()],
+    program.programId
+  );
+};
+
+export const getReferralInfo = async (program: Program, tagUID: BN, authority: PublicKey, user: PublicKey) => {
   return await PublicKey.findProgramAddress(
-    [Buffer.from(PREFIX), authority.toBuffer(), tagUID.toBuffer('le', 8), user.toBuffer()],
+    [Buffer.from(PREFIX), authority.toBuffer(), tagUID.toBuffer('le', 8), Buffer.from('referred'), user.toBuffer()],
     program.programId
   );
 };
@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use cupcake::cpi::accounts::ClaimTagV2;
+use cupcake::instructions::ClaimTagParams;
+use cupcake::program::Cupcake;
+use cupcake::state::CLAIM_AUTHORITY;
+
+declare_id!("21kG68EJVkVJxsbGGeBpY68XfvRt2XKZfhCZYf2RV9sY");
+
+/// Example of a Sprinkle's authority_program. Once registered with set_authority_program,
+/// it claims the Sprinkle over CPI, signing as its authority with the claim authority PDA
+/// [CLAIM_AUTHORITY, tag] of this program. This one only asks the user to sign;
+/// a real authority program checks its own conditions before forwarding the claim.
+#[program]
+pub mod claim_authority_example {
+
+    use super::*;
+
+    /// Claim a Sprinkle for the user. The remaining accounts are forwarded to claim_tag_v2 as they are.
+    pub fn claim<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, Claim<'info>>,
+        params: ClaimTagParams,
+    ) -> Result<()> {
+        let tag_key = ctx.accounts.tag.key();
+        let claim_authority_bump = *ctx.bumps.get("claim_authority").unwrap();
+        let claim_authority_seeds = &[CLAIM_AUTHORITY, tag_key.as_ref(), &[claim_authority_bump]];
+        let signer_seeds = &[&claim_authority_seeds[..]];
+
+        let cpi_accounts = ClaimTagV2 {
+            user: ctx.accounts.user.to_account_info(),
+            payer: ctx.accounts.payer.to_account_info(),
+            config: ctx.accounts.config.to_account_info(),
+            tag_authority: ctx.accounts.claim_authority.to_account_info(),
+            tag: ctx.accounts.tag.to_account_info(),
+            user_info: ctx.accounts.user_info.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+            quest: None,
+            quest_progress: None,
+            campaign: None,
+            campaign_user_info: None,
+            session_key: None,
+            treasury: None,
+            associated_token_program: None,
+            token_mint: None,
+            token_2022_program: None,
+            referrer: None,
+            referrer_info: None,
+            treasury_token: None,
+            referrer_token: None,
+            referral_info: None,
+        };
+        let context = CpiContext::new_with_signer(
+            ctx.accounts.cupcake_program.to_account_info(),
+            cpi_accounts,
+            signer_seeds,
+        )
+        .with_remaining_accounts(ctx.remaining_accounts.to_vec());
+
+        cupcake::cpi::claim_tag_v2(context, 0, params)
+    }
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    /// Account which receives the claim, and has to approve it.
+    pub user: Signer<'info>,
+
+    /// Account which pays the network and rent fees, for this transaction only.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Bakery PDA of the Sprinkle.
+    /// CHECK: Verified by Cupcake.
+    #[account(mut)]
+    pub config: UncheckedAccount<'info>,
+
+    /// This program's claim authority PDA for the Sprinkle, which signs the claim as its authority.
+    /// CHECK: Only used as a signer.
+    #[account(seeds = [CLAIM_AUTHORITY, tag.key().as_ref()], bump)]
+    pub claim_authority: UncheckedAccount<'info>,
+
+    /// PDA of the Sprinkle being claimed.
+    /// CHECK: Verified by Cupcake, which only accepts the claim authority PDA registered on it.
+    #[account(mut)]
+    pub tag: UncheckedAccount<'info>,
+
+    /// UserInfo PDA of the user for the Sprinkle's current season.
+    /// CHECK: Verified by Cupcake.
+    #[account(mut)]
+    pub user_info: UncheckedAccount<'info>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+
+    /// SPL Rent Sysvar, required for account allocation.
+    pub rent: Sysvar<'info, Rent>,
+
+    /// The Cupcake program.
+    pub cupcake_program: Program<'info, Cupcake>,
+}
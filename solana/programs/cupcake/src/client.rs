@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use std::collections::BTreeSet;
+use crate::errors::ErrorCode;
+use crate::instructions::BakeTagBatchParams;
+use crate::state::sprinkle::TagType;
+use crate::state::PDA_PREFIX;
+
+/// Largest serialized transaction the cluster accepts (IPv6 MTU minus headers).
+pub const MAX_TRANSACTION_SIZE: usize = 1232;
+
+/// Accounts of a single Sprinkle within a batch. token_mint is required by every type
+/// except CheckIn, token only by wallet restricted fungibles and 1/1s.
+#[derive(Clone, Copy)]
+pub struct BatchTagAccounts {
+    pub tag_authority: Pubkey,
+    pub token_mint: Option<Pubkey>,
+    pub token: Option<Pubkey>,
+}
+
+/// Splits tags into as many bake_tag_batch instructions as needed for each to fit
+/// in its own transaction. Tags get consecutive uids starting at params.start_uid.
+/// Fails if a tag is missing an account its type requires.
+pub fn bake_tag_batch_instructions(
+    authority: Pubkey,
+    payer: Pubkey,
+    params: BakeTagBatchParams,
+    tags: &[BatchTagAccounts],
+) -> Result<Vec<Instruction>> {
+    let mut instructions = vec![];
+    let mut start = 0;
+
+    while start < tags.len() {
+        // Grow the batch until the next tag would overflow the transaction.
+        let mut end = start + 1;
+        while end < tags.len() {
+            let candidate = bake_tag_batch_instruction(authority, payer, params, start, &tags[start..end + 1])?;
+            if transaction_size(&candidate, &[authority, payer]) > MAX_TRANSACTION_SIZE {
+                break;
+            }
+            end += 1;
+        }
+
+        instructions.push(bake_tag_batch_instruction(authority, payer, params, start, &tags[start..end])?);
+        start = end;
+    }
+
+    Ok(instructions)
+}
+
+fn bake_tag_batch_instruction(
+    authority: Pubkey,
+    payer: Pubkey,
+    params: BakeTagBatchParams,
+    offset: usize,
+    tags: &[BatchTagAccounts],
+) -> Result<Instruction> {
+    let (config, _) = Pubkey::find_program_address(&[PDA_PREFIX, authority.as_ref()], &crate::ID);
+    let params = BakeTagBatchParams {
+        start_uid: params.start_uid
+            .checked_add(offset as u64)
+            .ok_or(ErrorCode::NumericalOverflowError)?,
+        ..params
+    };
+
+    let mut accounts = crate::accounts::BakeTagBatch {
+        authority,
+        payer,
+        config,
+        system_program: anchor_lang::system_program::ID,
+        token_program: anchor_spl::token::ID,
+        rent: anchor_lang::solana_program::sysvar::rent::ID,
+    }
+    .to_account_metas(None);
+
+    for (i, tag_accounts) in tags.iter().enumerate() {
+        let uid = params.start_uid
+            .checked_add(i as u64)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+        let (tag, _) = Pubkey::find_program_address(
+            &[PDA_PREFIX, authority.as_ref(), &uid.to_le_bytes()],
+            &crate::ID,
+        );
+        accounts.push(AccountMeta::new(tag, false));
+        accounts.push(AccountMeta::new_readonly(tag_accounts.tag_authority, false));
+        if params.tag_type != TagType::CheckIn {
+            let token_mint = tag_accounts.token_mint.ok_or(ErrorCode::InvalidBatchBakeAccounts)?;
+            accounts.push(AccountMeta::new_readonly(token_mint, false));
+        }
+        if params.tag_type != TagType::CheckIn && params.tag_type != TagType::LimitedOrOpenEdition {
+            let token = tag_accounts.token.ok_or(ErrorCode::InvalidBatchBakeAccounts)?;
+            accounts.push(AccountMeta::new(token, false));
+        }
+    }
+
+    Ok(Instruction {
+        program_id: crate::ID,
+        accounts,
+        data: crate::instruction::BakeTagBatch { params }.data(),
+    })
+}
+
+/// Serialized size of a legacy transaction holding only the given instruction.
+fn transaction_size(instruction: &Instruction, signers: &[Pubkey]) -> usize {
+    let signers: BTreeSet<Pubkey> = signers.iter().copied().collect();
+    let mut keys: BTreeSet<Pubkey> = instruction.accounts.iter().map(|meta| meta.pubkey).collect();
+    keys.insert(instruction.program_id);
+    keys.extend(signers.iter().copied());
+
+    compact_len(signers.len()) + signers.len() * 64
+        // Message header, account keys and recent blockhash.
+        + 3 + compact_len(keys.len()) + keys.len() * 32 + 32
+        // A single instruction: program index, account indices and data.
+        + compact_len(1) + 1
+        + compact_len(instruction.accounts.len()) + instruction.accounts.len()
+        + compact_len(instruction.data.len()) + instruction.data.len()
+}
+
+/// Bytes taken by a length prefix in the short-vec encoding.
+fn compact_len(len: usize) -> usize {
+    match len {
+        0..=0x7f => 1,
+        0x80..=0x3fff => 2,
+        _ => 3,
+    }
+}
@@ -172,4 +172,226 @@ pub enum ErrorCode {
 
     #[msg("Not vaulted")]
     NotVaulted,
+
+    #[msg("This action is only available for Bundle tags")]
+    NotABundle,
+
+    #[msg("Bundle already holds the maximum number of slots")]
+    TooManyBundleSlots,
+
+    #[msg("Invalid bundle slot")]
+    InvalidBundleSlot,
+
+    #[msg("A non-fungible bundle slot can only be refilled once it has been claimed")]
+    BundleSlotNotEmpty,
+
+    #[msg("The provided token can not be traded in for this tag")]
+    InvalidTradeInToken,
+
+    #[msg("Not enough tokens to trade in")]
+    NotEnoughTradeInTokens,
+
+    #[msg("Vesting cliff must be between 0 and the vesting duration")]
+    InvalidVestingSchedule,
+
+    #[msg("No vested tokens are available to withdraw yet")]
+    NothingToWithdraw,
+
+    #[msg("The given user has already claimed the maximum amount of tokens from this tag for this period.")]
+    PeriodClaimLimitExceeded,
+
+    #[msg("Claim period can not be negative, and needs a positive per_period")]
+    InvalidClaimPeriod,
+
+    #[msg("The given user has not checked in to this tag")]
+    NotCheckedIn,
+
+    #[msg("Quest can hold at most 32 members")]
+    TooManyQuestMembers,
+
+    #[msg("Existing quest members can not be removed or reordered")]
+    QuestMembersImmutable,
+
+    #[msg("This tag is not a member of the quest")]
+    NotAQuestMember,
+
+    #[msg("This tag is part of a quest, so the quest and quest progress accounts are required")]
+    NoQuestPresent,
+
+    #[msg("The quest has not been completed yet")]
+    QuestNotCompleted,
+
+    #[msg("This tag is part of a campaign, so the campaign and campaign user info accounts are required")]
+    NoCampaignPresent,
+
+    #[msg("The campaign of this tag cannot output any more tokens.")]
+    CampaignDepleted,
+
+    #[msg("The given user has already claimed the maximum amount of tokens from this campaign.")]
+    CampaignClaimLimitExceeded,
+
+    #[msg("Bundle supply is tracked per slot, so bundles can not start a new season")]
+    BundleCanNotStartNewSeason,
+
+    #[msg("The given signer is not an authority of this tag")]
+    InvalidTagAuthority,
+
+    #[msg("Tag can hold at most 3 additional authorities")]
+    TooManyTagAuthorities,
+
+    #[msg("Session key can be scoped to at most 16 tags")]
+    TooManySessionKeyTags,
+
+    #[msg("Session key is not scoped to this tag")]
+    SessionKeyOutOfScope,
+
+    #[msg("Session key has expired")]
+    SessionKeyExpired,
+
+    #[msg("Session key has no claims left in its budget")]
+    SessionKeyBudgetExhausted,
+
+    #[msg("Invalid gate, lock gates need an amount of 1")]
+    InvalidTagGate,
+
+    #[msg("User does not hold the token required by this tag")]
+    GateNotPassed,
+
+    #[msg("This gate token has already been used on this tag")]
+    GateTokenAlreadyUsed,
+
+    #[msg("User is not on this tag's allowlist")]
+    NotOnAllowlist,
+
+    #[msg("Revenue splits must have between 1 and 5 recipients, with shares adding up to 10000 basis points")]
+    InvalidRevenueSplits,
+
+    #[msg("Missing accounts to pay the claim price")]
+    MissingClaimPriceAccounts,
+
+    #[msg("Claim price is above the maximum price accepted by the claimer")]
+    ClaimPriceTooHigh,
+
+    #[msg("Treasury does not hold enough lamports")]
+    InsufficientTreasuryFunds,
+
+    #[msg("Escrow duration can not be negative")]
+    InvalidEscrowDuration,
+
+    #[msg("This tag does not support claims to an identity")]
+    IdentityClaimNotSupported,
+
+    #[msg("Claim escrow still holds tokens of another mint")]
+    ClaimEscrowMintMismatch,
+
+    #[msg("Claim escrow has not expired yet")]
+    ClaimEscrowNotExpired,
+
+    #[msg("Missing accounts to create the user's associated token account")]
+    MissingAtaAccounts,
+
+    #[msg("Program-created mints need the Bakery PDA as new mint authority")]
+    InvalidNewMintAuthority,
+
+    #[msg("This type of tag can not be airdropped")]
+    AirdropNotSupported,
+
+    #[msg("Airdrop accounts do not match the tag type")]
+    InvalidAirdropAccounts,
+
+    #[msg("This type of tag can not be baked in a batch")]
+    BatchBakeNotSupported,
+
+    #[msg("Batch bake accounts do not match the tag type")]
+    InvalidBatchBakeAccounts,
+
+    #[msg("Tag has already been baked")]
+    TagAlreadyBaked,
+
+    #[msg("Tag was not baked from this template")]
+    TagNotFromTemplate,
+
+    #[msg("Tag params do not match the template")]
+    TemplateMismatch,
+
+    #[msg("Invalid claim hook")]
+    InvalidClaimHook,
+
+    #[msg("Missing claim hook accounts")]
+    MissingClaimHookAccounts,
+
+    #[msg("Invalid authority program")]
+    InvalidAuthorityProgram,
+
+    #[msg("Referrer can not be the user")]
+    SelfReferral,
+
+    #[msg("Referrer has reached their referral cap")]
+    ReferrerCapReached,
+
+    #[msg("Missing referral reward accounts")]
+    MissingReferralAccounts,
+
+    #[msg("Invalid raffle")]
+    InvalidRaffle,
+
+    #[msg("Raffle is closed for entries")]
+    RaffleClosed,
+
+    #[msg("User has already entered this raffle")]
+    AlreadyEnteredRaffle,
+
+    #[msg("Raffle is still open for entries")]
+    RaffleStillOpen,
+
+    #[msg("Raffle has already been drawn")]
+    RaffleAlreadyDrawn,
+
+    #[msg("Raffle has not been drawn yet")]
+    RaffleNotDrawn,
+
+    #[msg("Entry did not win the raffle")]
+    NotARaffleWinner,
+
+    #[msg("Winning entries must claim their prize instead")]
+    RaffleWinnerCanNotClose,
+
+    #[msg("Invalid combo keys")]
+    InvalidComboKeys,
+
+    #[msg("Missing combo accounts")]
+    MissingComboAccounts,
+
+    #[msg("A combo key has not attested to this claim")]
+    ComboAttestationMissing,
+
+    #[msg("Another bundle slot already holds this token")]
+    DuplicateBundleMint,
+
+    #[msg("This tag is already on the current version")]
+    TagAlreadyMigrated,
+
+    #[msg("A vesting schedule already exists for this claim")]
+    VestingScheduleStarted,
+
+    #[msg("Quest can not require more members than it holds")]
+    InvalidQuestRequirement,
+
+    #[msg("The previous reward tag must be passed to unlink it from the quest")]
+    MissingPreviousRewardTag,
+
+    #[msg("The raffle's draw slot has not passed yet")]
+    RaffleDrawSlotPending,
+
+    #[msg("The combo attestations to this claim have expired")]
+    ComboAttestationExpired,
+
+    #[msg("Sprinkle already belongs to another quest")]
+    TagInAnotherQuest,
+
+    #[msg("The ClaimEscrow must be passed as a writable user account")]
+    ClaimEscrowNotWritable,
+
+    #[msg("Token-2022 transfers need the token mint and the Token-2022 program")]
+    MissingToken2022Accounts,
 }
@@ -0,0 +1,274 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::Token;
+use crate::errors::ErrorCode;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, campaign::*, quest::*, sprinkle::*, user_info::*};
+use crate::utils::{
+    load_or_create_program_account, token_2022,
+    deliver_sprinkle, DeliverSprinkleArgs, SprinkleDelivery,
+    begin_claim, BeginClaimArgs, record_claim, RecordClaimArgs, run_claim_hook,
+};
+
+#[derive(Accounts)]
+pub struct AirdropFromTag<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// Account which pays the network and rent fees, for this transaction only.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+
+    /// SPL Associated Token Account Program, required to create recipient ATAs.
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    /// SPL Rent Sysvar, required for account allocation.
+    pub rent: Sysvar<'info, Rent>,
+
+    /// PDA of the Quest this Sprinkle is part of. Required if the Sprinkle has a quest set.
+    pub quest: Option<Box<Account<'info, Quest>>>,
+
+    /// PDA of the Campaign this Sprinkle draws from. Required if the Sprinkle has a campaign set.
+    #[account(mut)]
+    pub campaign: Option<Box<Account<'info, Campaign>>>,
+
+    /// SPL Token-2022 Program, required if the Sprinkle hands out Token-2022 tokens.
+    /// CHECK: Verified by address.
+    #[account(address = token_2022::ID)]
+    pub token_2022_program: Option<UncheckedAccount<'info>>,
+}
+
+// Remaining accounts -
+    // SingleUse1Of1, Refillable1Of1, WalletRestrictedFungible without a vesting schedule:
+        // token (w) - ata of token_mint type owned by config authority wallet
+        // token_mint - token mint on the tag
+        // claim hook accounts - if the tag has a claim hook set, see claim_sprinkle
+        // for each recipient:
+            // recipient - wallet receiving the tokens
+            // user_info (w) - UserInfo PDA of the recipient, initialized if not setup
+            // recipient_ata (w) - ata of token_mint type for recipient, created if missing
+            // quest_progress (w) - QuestProgress PDA of the recipient, if the tag has a quest set
+            // campaign_user_info (w) - CampaignUserInfo PDA of the recipient, if the tag has a campaign set
+    //
+    // LimitedOrOpenEdition:
+        // token_mint - token mint on the tag
+        // token (w) - ata of config authority containing the token
+        // metadata - existing metadata of current token_mint
+        // master_edition (w) - existing master edition of current token_mint
+        // update_authority - Authority of metadata
+        // token_metadata_program
+        // claim hook accounts - if the tag has a claim hook set, see claim_sprinkle
+        // for each recipient:
+            // recipient - wallet receiving the edition
+            // user_info (w) - UserInfo PDA of the recipient, initialized if not setup
+            // new_token_mint (w) - uninitialized PDA with seed [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), MINT, &edition.to_le_bytes()]
+            // new_metadata (w) - precomputed new metadata key
+            // new_edition (w) - precomputed new edition key
+            // edition_mark_pda (w) - What edition page this edition is on
+            // recipient_new_token (w) - ata of new_token_mint type for recipient, created along with the mint
+            // quest_progress (w) - QuestProgress PDA of the recipient, if the tag has a quest set
+            // campaign_user_info (w) - CampaignUserInfo PDA of the recipient, if the tag has a campaign set
+// -
+
+/// Hands out a Sprinkle to a list of recipients without scans, sharing the claim limits of the Sprinkle,
+/// counting towards its Quest and Campaign and running its claim hook like a scan would.
+/// Gates, prices and allowlists only apply to scans.
+///
+/// Only Sprinkles which hand out a token the Bakery already holds, or an edition of it, can be airdropped:
+/// SingleUse1Of1, Refillable1Of1, WalletRestrictedFungible without a vesting schedule, and LimitedOrOpenEdition.
+/// Bundles, CandyMachineDrops, HotPotatos, ProgrammableUniques, vesting Sprinkles, Raffles, CheckIns and TradeIns
+/// need accounts or a signature from the user, so they fail with AirdropNotSupported and must be claimed by scan.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, AirdropFromTag<'info>>,
+) -> Result<()> {
+    let tag = &mut ctx.accounts.tag;
+    let config = &ctx.accounts.config;
+    let config_info = config.to_account_info();
+    let payer = &ctx.accounts.payer;
+    let config_seeds = &[PDA_PREFIX, config.authority.as_ref(), &[config.bump]];
+    let now = Clock::get()?.unix_timestamp;
+
+    let (num_shared_accounts, num_delivery_accounts) = match tag.tag_type {
+        TagType::SingleUse1Of1 | TagType::Refillable1Of1 => (2, 3),
+        TagType::WalletRestrictedFungible if tag.vesting_duration == 0 => (2, 3),
+        TagType::LimitedOrOpenEdition => (6, 7),
+        _ => return Err(ErrorCode::AirdropNotSupported.into()),
+    };
+    let num_recipient_accounts = num_delivery_accounts
+        + usize::from(tag.quest.is_some())
+        + usize::from(tag.campaign.is_some());
+    let num_hook_accounts = tag.num_hook_accounts();
+    let (shared_accounts, recipient_accounts) = ctx.remaining_accounts.split_at(
+        std::cmp::min(num_shared_accounts + num_hook_accounts, ctx.remaining_accounts.len())
+    );
+    let (shared_accounts, hook_accounts) = shared_accounts.split_at(
+        std::cmp::min(num_shared_accounts, shared_accounts.len())
+    );
+    require!(
+        shared_accounts.len() == num_shared_accounts
+            && hook_accounts.len() == num_hook_accounts
+            && recipient_accounts.len() % num_recipient_accounts == 0,
+        ErrorCode::InvalidAirdropAccounts
+    );
+
+    for accounts in recipient_accounts.chunks(num_recipient_accounts) {
+        let recipient = &accounts[0];
+        let mut ctr = num_delivery_accounts;
+
+        // Load the recipient's UserInfo for the current season, the same one their scans use.
+        let (mut user_info, user_info_bump) = load_or_create_program_account::<UserInfo>(
+            &accounts[1],
+            &payer.to_account_info(),
+            &ctx.accounts.system_program,
+            &ctx.accounts.rent,
+            UserInfo::SIZE,
+            &[
+                PDA_PREFIX,
+                config.authority.as_ref(),
+                &tag.uid.to_le_bytes(),
+                recipient.key().as_ref(),
+                &tag.season_seed(),
+            ],
+        )?;
+        user_info.bump = user_info_bump;
+
+        // Ensure neither the Sprinkle's total_supply nor the recipient's per_user value have been reached.
+        if tag.total_supply > 0 && tag.num_claimed >= tag.total_supply {
+            return Err(ErrorCode::TagDepleted.into());
+        }
+        require!(user_info.num_claimed < tag.per_user, ErrorCode::ClaimLimitExceeded);
+
+        // Ensure the recipient has not reached the Sprinkle's per_period value within their current period.
+        require!(
+            tag.claim_period == 0 || user_info.current_period_claims(tag.claim_period, now) < tag.per_period,
+            ErrorCode::PeriodClaimLimitExceeded
+        );
+
+        let quest_progress = tag.quest.map(|_| {
+            ctr += 1;
+            &accounts[ctr - 1]
+        });
+        let campaign_user_info = tag.campaign.map(|_| {
+            ctr += 1;
+            &accounts[ctr - 1]
+        });
+        let (campaign_user_info, campaign_remaining) = begin_claim(BeginClaimArgs {
+            tag,
+            config,
+            user: recipient.key(),
+            payer: &payer.to_account_info(),
+            quest: ctx.accounts.quest.as_deref(),
+            quest_progress,
+            campaign: ctx.accounts.campaign.as_deref(),
+            campaign_user_info,
+            rent: &ctx.accounts.rent,
+            system_program: &ctx.accounts.system_program,
+        })?;
+
+        let mut amount_to_claim = 1;
+
+        let delivery = match tag.tag_type {
+            TagType::LimitedOrOpenEdition => SprinkleDelivery::Edition {
+                token_mint: &shared_accounts[0],
+                token: &shared_accounts[1],
+                metadata: &shared_accounts[2],
+                master_edition: &shared_accounts[3],
+                update_authority: &shared_accounts[4],
+                token_metadata_program: &shared_accounts[5],
+                new_token_mint: &accounts[2],
+                new_metadata: &accounts[3],
+                new_edition: &accounts[4],
+                edition_mark_pda: &accounts[5],
+                new_mint_authority: &config_info,
+                user_new_token: Some(&accounts[6]),
+            },
+
+            _ => {
+                amount_to_claim = std::cmp::min(
+                    tag.fungible_claim_amount(tag.per_user, user_info.num_claimed)?,
+                    campaign_remaining,
+                );
+                SprinkleDelivery::Tokens {
+                    token: &shared_accounts[0],
+                    user_ata: &accounts[2],
+                    token_mint: Some(shared_accounts[1].clone()),
+                    expected_mint: tag.token_mint,
+                    amount: amount_to_claim,
+                }
+            }
+        };
+
+        deliver_sprinkle(DeliverSprinkleArgs {
+            delivery,
+            tag,
+            config,
+            user: recipient,
+            payer: &payer.to_account_info(),
+            ata_program: Some(ctx.accounts.associated_token_program.to_account_info()),
+            rent: &ctx.accounts.rent,
+            system_program: &ctx.accounts.system_program,
+            token_program: &ctx.accounts.token_program,
+            token_2022_program: ctx.accounts.token_2022_program
+                .as_ref()
+                .map(|program| program.to_account_info()),
+            config_seeds,
+        })?;
+
+        // Update the same counters a scan would.
+        record_claim(RecordClaimArgs {
+            tag,
+            user_info: &mut user_info,
+            campaign: ctx.accounts.campaign.as_deref_mut(),
+            campaign_user_info,
+            amount: amount_to_claim,
+            now,
+        })?;
+
+        let claim_index = tag.num_claimed;
+        tag.num_claimed = tag.num_claimed
+            .checked_add(amount_to_claim)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+        tag.last_claimed_at = now;
+
+        // Run the Sprinkle's claim hook for every recipient, the same as for a scan, so hook-gated
+        // Sprinkles can't be handed out around it. The counters are written out first, so the hook reads them.
+        if tag.claim_hook.is_some() {
+            let args = ClaimHookArgs {
+                tag: tag.key(),
+                user: recipient.key(),
+                amount: amount_to_claim,
+                claim_index,
+            };
+            tag.exit(ctx.program_id)?;
+            run_claim_hook(hook_accounts, tag, &config.authority, args)?;
+        }
+    }
+
+    Ok(())
+}
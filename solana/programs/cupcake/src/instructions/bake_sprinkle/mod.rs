@@ -5,7 +5,7 @@ use mpl_token_metadata::processor::AuthorizationData;
 use mpl_token_metadata::state::{Metadata, TokenMetadataAccount, TokenRecord};
 use crate::errors::ErrorCode;
 use crate::state::PDA_PREFIX;
-use crate::state::{bakery::*, sprinkle::*};
+use crate::state::{bakery::*, sprinkle::*, tag_template::*};
 use crate::utils::{assert_is_ata, assert_keys_equal};
 use anchor_lang::solana_program::{program::invoke_signed, system_program};
 use anchor_spl::token::*;
@@ -35,6 +35,46 @@ pub struct AddOrRefillTagParams {
 
     /// If this is true, whitelist tokens will be burnt after being used to mint from the Candy Machine.
     whitelist_burn: bool,
+
+    /// If this is true, the TradeIn input mint is a collection, and any verified member is accepted.
+    input_is_collection: bool,
+
+    /// The amount of input tokens claimers must hand in on each claim of a TradeIn.
+    input_amount: u64,
+
+    /// If this is true, TradeIn input tokens will be burnt instead of being sent to the Bakery authority.
+    input_burn: bool,
+
+    /// Number of seconds after each claim before any of its fungible tokens unlock.
+    vesting_cliff: i64,
+
+    /// Number of seconds after each claim until all of its fungible tokens are unlocked.
+    /// Set to 0 to transfer claimed tokens outright.
+    vesting_duration: i64,
+
+    /// Length in seconds of the period over which per_period is enforced. Set to 0 to disable period limits.
+    claim_period: i64,
+
+    /// The number of claims an individual user can execute from this Sprinkle within one claim_period.
+    per_period: u64,
+
+    /// The holding requirement claimers must meet, if any.
+    gate: Option<TagGate>,
+
+    /// The total lamports the Bakery Treasury can spend reimbursing the rent costs of claims.
+    sponsorship_limit: u64,
+
+    /// Number of seconds tokens claimed to an identity stay redeemable. Set to 0 to disable claims to an identity.
+    escrow_duration: i64,
+
+    /// Unix timestamp after which a Raffle takes no more entries, and can be drawn.
+    raffle_ends_at: i64,
+
+    /// The amount of tokens each Raffle winner receives.
+    raffle_prize: u64,
+
+    /// If this is true, an existing Sprinkle starts a new season, resetting its claim counters.
+    new_season: bool,
 }
 
 #[derive(Accounts)]
@@ -83,12 +123,22 @@ pub struct AddOrRefillTag<'info> {
 
     /// SPL Rent Sysvar, required for account allocation.
     pub rent: Sysvar<'info, Rent>,
+
+    /// PDA which stores the defaults this Sprinkle is baked from, if any.
+    /// Re-baking without it detaches the Sprinkle from its template.
+    #[account(has_one = config)]
+    pub template: Option<Box<Account<'info, TagTemplate>>>,
 }
 
 // Remaining accounts - if doing wallet restricted fungible, or either 1/1 option, pass:
     // token_mint
     // token (w) - ata of token_mint type
     //
+    // If doing trade in, pass:
+    // token_mint
+    // token (w) - ata of token_mint type
+    // input_mint - mint (or collection mint) of the token claimers hand in
+    //
     // If doing hotpotato, pass
     // token_mint
     // token (w) - ata of token_mint type
@@ -104,11 +154,37 @@ pub struct AddOrRefillTag<'info> {
     // whitelist_token - ata of whitelist_mint type, if present, we use this to pay with.
     // payment_token_mint - if system, we assume you pay in sol. Otherwise user will need to provide this.
     // payment_token - ata of payment token type to approve use of, if not system.
+    //
+    // If doing bundle, pass nothing. Slots are added afterwards with refill_bundle_slot.
+    //
+    // If doing check in, pass nothing.
+    //
+    // If doing raffle, pass:
+    // token_mint
+    // token (w) - ata of token_mint type
 
 pub fn handler<'a, 'b, 'c, 'info>(
     ctx: Context<'a, 'b, 'c, 'info, AddOrRefillTag<'info>>,
     tag_params: AddOrRefillTagParams
 ) -> Result<()> {   
+  // Sprinkles baked from a template take its defaults over the provided params.
+  let tag_params = match &ctx.accounts.template {
+      Some(template) => {
+          require!(template.tag_type == tag_params.tag_type, ErrorCode::TemplateMismatch);
+          AddOrRefillTagParams {
+              num_claims: template.num_claims,
+              per_user: template.per_user,
+              claim_period: template.claim_period,
+              per_period: template.per_period,
+              gate: template.gate,
+              sponsorship_limit: template.sponsorship_limit,
+              escrow_duration: template.escrow_duration,
+              ..tag_params
+          }
+      }
+      None => tag_params,
+  };
+
   let tag_type = tag_params.tag_type;
   let token_program = &ctx.accounts.token_program;
   let minter_pays = tag_params.minter_pays;
@@ -123,6 +199,34 @@ pub fn handler<'a, 'b, 'c, 'info>(
       ErrorCode::SingleUseIsImmutable
   );
 
+  // Starting a new season resets the Sprinkle's counters, as if it was freshly baked.
+  // Users get a new UserInfo for the new season, so their previous claims stay on record.
+  // A freshly created Sprinkle has no Bakery set yet, whatever its uid.
+  let is_existing_tag = tag.config != Pubkey::default();
+  if tag_params.new_season && is_existing_tag {
+      require!(
+          tag.tag_type != TagType::Bundle && tag_type != TagType::Bundle,
+          ErrorCode::BundleCanNotStartNewSeason
+      );
+      tag.season = tag.season
+          .checked_add(1)
+          .ok_or(ErrorCode::NumericalOverflowError)?;
+      tag.num_claimed = 0;
+      tag.total_supply = 0;
+      tag.num_entries = 0;
+      tag.raffle_seed = None;
+      tag.raffle_draw_slot = 0;
+  }
+
+  // A Sprinkle changing type drops the state of any Raffle it held, so no draw carries over into the new type.
+  if is_existing_tag && tag.tag_type != tag_type {
+      tag.raffle_ends_at = 0;
+      tag.raffle_prize = 0;
+      tag.num_entries = 0;
+      tag.raffle_seed = None;
+      tag.raffle_draw_slot = 0;
+  }
+
   // Determine the total_supply of the new or updated Sprinkle.
   let total_supply = match tag_type {
       // SingleUse1Of1s and HotPotatos can only have a total_supply of 1.
@@ -140,6 +244,16 @@ pub fn handler<'a, 'b, 'c, 'info>(
           }
       }
 
+      // Bundle supply is managed per slot by refill_bundle_slot, so leave it as is.
+      // A Sprinkle converted into a Bundle has no claims left until its slots are filled.
+      TagType::Bundle => {
+          if tag.tag_type == TagType::Bundle {
+              tag.total_supply
+          } else {
+              tag.num_claimed
+          }
+      }
+
       // For all other cases, accept the user-provided value.
       _ => tag_params.num_claims,
   };
@@ -150,6 +264,7 @@ pub fn handler<'a, 'b, 'c, 'info>(
       | TagType::Refillable1Of1
       | TagType::ProgrammableUnique
       | TagType::WalletRestrictedFungible
+      | TagType::TradeIn
       | TagType::HotPotato => {
           let token_mint = &ctx.remaining_accounts[0];
           let token = &ctx.remaining_accounts[1];
@@ -166,7 +281,10 @@ pub fn handler<'a, 'b, 'c, 'info>(
           let _mint: Account<Mint> = Account::try_from(token_mint)?;
           let token_account: Account<TokenAccount> = Account::try_from(token)?;
           let mut is_programmable = false;
-          if ctx.remaining_accounts.len() > 2 && tag_type != TagType::HotPotato {
+          if ctx.remaining_accounts.len() > 2
+              && tag_type != TagType::HotPotato
+              && tag_type != TagType::TradeIn
+          {
               let token_metadata = Metadata::from_account_info(&ctx.remaining_accounts[2])?;
               is_programmable = token_metadata.programmable_config != None;
           }
@@ -361,6 +479,21 @@ pub fn handler<'a, 'b, 'c, 'info>(
               }
           }
 
+          // If the Sprinkle is a TradeIn, store what claimers need to hand in.
+          if tag_type == TagType::TradeIn {
+              let input_mint = &ctx.remaining_accounts[2];
+
+              // Verify that the provided input mint is legitimate.
+              // Collections are themselves NFTs, so this holds for them too.
+              let _input_mint: Account<Mint> = Account::try_from(input_mint)?;
+              require!(tag_params.input_amount > 0, ErrorCode::InvalidTradeInToken);
+
+              tag.input_mint = input_mint.key();
+              tag.input_is_collection = tag_params.input_is_collection;
+              tag.input_amount = tag_params.input_amount;
+              tag.input_burn = tag_params.input_burn;
+          }
+
           token_mint.key()
       }
 
@@ -448,6 +581,54 @@ pub fn handler<'a, 'b, 'c, 'info>(
           payment_token_mint.key()
       }
 
+      // Bundle assets are delegated slot by slot through refill_bundle_slot.
+      TagType::Bundle => system_program::ID,
+
+      // CheckIns move no assets, so there is nothing to delegate.
+      TagType::CheckIn => system_program::ID,
+
+      TagType::Raffle => {
+          let token_mint = &ctx.remaining_accounts[0];
+          let token = &ctx.remaining_accounts[1];
+
+          // Entries are open until raffle_ends_at, and can't be reopened once the draw is committed to.
+          // A Raffle needs at least one winner.
+          require!(
+              tag_params.raffle_ends_at > 0
+                  && tag_params.raffle_prize > 0
+                  && total_supply > 0
+                  && tag.raffle_draw_slot == 0
+                  && tag.raffle_seed.is_none(),
+              ErrorCode::InvalidRaffle
+          );
+
+          // Check that the provided token accounts are legitimate, then delegate
+          // enough tokens for every winner's prize to the Bakery PDA.
+          let _mint: Account<Mint> = Account::try_from(token_mint)?;
+          assert_is_ata(
+              token,
+              &ctx.accounts.config.authority.key(),
+              &token_mint.key(),
+              Some(&ctx.accounts.config.key()),
+          )?;
+          let cpi_accounts = Approve {
+              to: token.clone(),
+              delegate: ctx.accounts.config.to_account_info(),
+              authority: ctx.accounts.authority.to_account_info(),
+          };
+          let context = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+          approve(
+              context,
+              tag_params.raffle_prize
+                  .checked_mul(total_supply)
+                  .ok_or(ErrorCode::NumericalOverflowError)?,
+          )?;
+
+          tag.raffle_ends_at = tag_params.raffle_ends_at;
+          tag.raffle_prize = tag_params.raffle_prize;
+          token_mint.key()
+      }
+
       TagType::LimitedOrOpenEdition => {
           // Verify that the provided token mint is legitimate.
           let token_mint = &ctx.remaining_accounts[0];
@@ -457,8 +638,9 @@ pub fn handler<'a, 'b, 'c, 'info>(
   };
 
   // If the Sprinkle is a SingleUse1Of1, the per_user and total_supply values will both always be 1.
+  // Raffles take a single entry per user, so their per_user is always 1 too.
   tag.per_user = match tag_type {
-      TagType::SingleUse1Of1 => 1,
+      TagType::SingleUse1Of1 | TagType::Raffle => 1,
       _ => tag_params.per_user,
   };
   if tag_type != TagType::SingleUse1Of1 {
@@ -467,8 +649,38 @@ pub fn handler<'a, 'b, 'c, 'info>(
       tag.total_supply = 1;
   }
 
+  // Only WalletRestrictedFungibles can vest, every other type hands its tokens out directly.
+  if tag_type == TagType::WalletRestrictedFungible {
+      require!(
+          tag_params.vesting_cliff >= 0
+              && tag_params.vesting_cliff <= tag_params.vesting_duration,
+          ErrorCode::InvalidVestingSchedule
+      );
+      tag.vesting_cliff = tag_params.vesting_cliff;
+      tag.vesting_duration = tag_params.vesting_duration;
+  } else {
+      tag.vesting_cliff = 0;
+      tag.vesting_duration = 0;
+  }
+
+  // Period limits are stored as is, with a claim_period of 0 turning them off.
+  Tag::validate_claim_period(tag_params.claim_period, tag_params.per_period)?;
+  tag.claim_period = tag_params.claim_period;
+  tag.per_period = tag_params.per_period;
+
+  // Gates need a positive amount, and a Lock gate can only track single tokens.
+  if let Some(gate) = tag_params.gate {
+      gate.validate()?;
+  }
+  tag.gate = tag_params.gate;
+  tag.sponsorship_limit = tag_params.sponsorship_limit;
+
+  // Claims to an identity need a positive window for the tokens to be redeemed in.
+  require!(tag_params.escrow_duration >= 0, ErrorCode::InvalidEscrowDuration);
+  tag.escrow_duration = tag_params.escrow_duration;
+
   // Store information about the claim method and underlying assets in the Sprinkle's state.
-  // Currently, counters are left unchanged after re-bakes. 
+  // Counters are left unchanged after re-bakes, unless a new season was started above.
   tag.minter_pays = minter_pays;
   tag.uid = tag_params.uid;
   tag.tag_authority = *ctx.accounts.tag_authority.to_account_info().key;
@@ -476,6 +688,16 @@ pub fn handler<'a, 'b, 'c, 'info>(
   tag.token_mint = token_mint;
   tag.config = ctx.accounts.config.key();
   tag.bump = *ctx.bumps.get("tag").unwrap();
+  tag.version = Tag::VERSION;
+
+  // Remember the template, so its later changes can be synced onto the Sprinkle.
+  match &ctx.accounts.template {
+      Some(template) => {
+          template.apply_to(tag);
+          tag.template = Some(template.key());
+      }
+      None => tag.template = None,
+  }
   
   Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,187 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token::*;
+use crate::errors::ErrorCode;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, sprinkle::*};
+use crate::utils::{assert_derivation, assert_is_ata, create_or_allocate_account_raw};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq)]
+pub struct BakeTagBatchParams {
+    /// The uid of the first Sprinkle of the batch. The others follow it one by one.
+    pub start_uid: u64,
+
+    /// The claim method every Sprinkle of the batch will use.
+    pub tag_type: TagType,
+
+    /// The total amount of claims that can be executed from each Sprinkle.
+    pub num_claims: u64,
+
+    /// The total number of claims an individual user can execute from each Sprinkle.
+    pub per_user: u64,
+
+    /// Length in seconds of the period over which per_period is enforced. Set to 0 to disable period limits.
+    pub claim_period: i64,
+
+    /// The number of claims an individual user can execute from each Sprinkle within one claim_period.
+    pub per_period: u64,
+
+    /// The holding requirement claimers must meet, if any.
+    pub gate: Option<TagGate>,
+
+    /// The total lamports the Bakery Treasury can spend reimbursing the rent costs of claims, per Sprinkle.
+    pub sponsorship_limit: u64,
+
+    /// Number of seconds tokens claimed to an identity stay redeemable. Set to 0 to disable claims to an identity.
+    pub escrow_duration: i64,
+}
+
+#[derive(Accounts)]
+pub struct BakeTagBatch<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Account which pays the network and rent fees, for this transaction only.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+
+    /// SPL Rent Sysvar, required for account allocation.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+// Remaining accounts - for each Sprinkle of the batch, in uid order:
+    // tag (w) - uninitialized Sprinkle PDA with seed [PREFIX, config.authority.as_ref(), &uid.to_le_bytes()]
+    // tag_authority - account which has the authority to execute claims for this Sprinkle
+    // If doing wallet restricted fungible, or either 1/1 option, also pass:
+        // token_mint
+        // token (w) - ata of token_mint type
+    // If doing limited/open edition, also pass:
+        // token_mint
+    // If doing check in, pass nothing more.
+// -
+
+/// Bakes a batch of new Sprinkles sharing the same parameters, with consecutive uids.
+/// Only Sprinkle types which need no more than a token delegation are supported.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, BakeTagBatch<'info>>,
+    params: BakeTagBatchParams,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let tag_type = params.tag_type;
+
+    let num_tag_accounts = match tag_type {
+        TagType::SingleUse1Of1 | TagType::Refillable1Of1 | TagType::WalletRestrictedFungible => 4,
+        TagType::LimitedOrOpenEdition => 3,
+        TagType::CheckIn => 2,
+        _ => return Err(ErrorCode::BatchBakeNotSupported.into()),
+    };
+    require!(
+        ctx.remaining_accounts.chunks_exact(num_tag_accounts).remainder().is_empty(),
+        ErrorCode::InvalidBatchBakeAccounts
+    );
+
+    // Validate the shared parameters the same way add_or_refill_tag does.
+    Tag::validate_claim_period(params.claim_period, params.per_period)?;
+    require!(params.escrow_duration >= 0, ErrorCode::InvalidEscrowDuration);
+    if let Some(gate) = params.gate {
+        gate.validate()?;
+    }
+
+    // SingleUse1Of1s and Refillable1Of1s hold a single NFT, every other type accepts the provided value.
+    let (total_supply, per_user) = match tag_type {
+        TagType::SingleUse1Of1 => (1, 1),
+        TagType::Refillable1Of1 => (1, params.per_user),
+        _ => (params.num_claims, params.per_user),
+    };
+
+    for (i, accounts) in ctx.remaining_accounts.chunks(num_tag_accounts).enumerate() {
+        let tag_info = &accounts[0];
+        let tag_authority = &accounts[1];
+        let uid = params.start_uid
+            .checked_add(i as u64)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+
+        // Batches only bake new Sprinkles, re-bakes go through add_or_refill_tag.
+        require!(tag_info.data_is_empty(), ErrorCode::TagAlreadyBaked);
+        let tag_seeds = [PDA_PREFIX, config.authority.as_ref(), &uid.to_le_bytes()];
+        let tag_bump = assert_derivation(ctx.program_id, tag_info, &tag_seeds)?;
+        create_or_allocate_account_raw(
+            *ctx.program_id,
+            tag_info,
+            &ctx.accounts.rent,
+            &ctx.accounts.system_program,
+            &ctx.accounts.payer.to_account_info(),
+            Tag::SIZE,
+            &[PDA_PREFIX, config.authority.as_ref(), &uid.to_le_bytes(), &[tag_bump]],
+        )?;
+        tag_info.try_borrow_mut_data()?[..8].copy_from_slice(&Tag::discriminator());
+        let mut tag: Account<Tag> = Account::try_from(tag_info)?;
+
+        // Delegate each Sprinkle's tokens to the Bakery PDA, as add_or_refill_tag does.
+        let token_mint = match tag_type {
+            TagType::SingleUse1Of1 | TagType::Refillable1Of1 | TagType::WalletRestrictedFungible => {
+                let token_mint = &accounts[2];
+                let token = &accounts[3];
+                let _mint: Account<Mint> = Account::try_from(token_mint)?;
+                assert_is_ata(
+                    token,
+                    &config.authority,
+                    &token_mint.key(),
+                    Some(&config.key()),
+                )?;
+
+                let cpi_accounts = Approve {
+                    to: token.clone(),
+                    delegate: config.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                };
+                let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+                approve(context, total_supply)?;
+
+                token_mint.key()
+            }
+
+            TagType::LimitedOrOpenEdition => {
+                let token_mint = &accounts[2];
+                let _mint: Account<Mint> = Account::try_from(token_mint)?;
+                token_mint.key()
+            }
+
+            _ => anchor_lang::solana_program::system_program::ID,
+        };
+
+        tag.uid = uid;
+        tag.tag_type = tag_type;
+        tag.tag_authority = tag_authority.key();
+        tag.config = config.key();
+        tag.token_mint = token_mint;
+        tag.total_supply = total_supply;
+        tag.per_user = per_user;
+        tag.claim_period = params.claim_period;
+        tag.per_period = params.per_period;
+        tag.gate = params.gate;
+        tag.sponsorship_limit = params.sponsorship_limit;
+        tag.escrow_duration = params.escrow_duration;
+        tag.bump = tag_bump;
+        tag.version = Tag::VERSION;
+        tag.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, RAFFLE};
+use crate::state::{bakery::*, raffle::*, sprinkle::*};
+use crate::utils::assert_is_ata;
+
+#[derive(Accounts)]
+pub struct ClaimRafflePrize<'info> {
+    /// The user who entered the Raffle.
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    pub config: Box<Account<'info, Config>>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              constraint = tag.tag_type == TagType::Raffle @ ErrorCode::InvalidRaffle,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// PDA which stores the user's entry in the Raffle. Closed once the prize is claimed.
+    #[account(mut,
+              close = rent_payer,
+              has_one = tag,
+              has_one = user,
+              has_one = rent_payer,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  &tag.uid.to_le_bytes(),
+                  RAFFLE,
+                  user.key().as_ref(),
+                  &tag.season_seed()
+              ],
+              bump = raffle_entry.bump)]
+    pub raffle_entry: Box<Account<'info, RaffleEntry>>,
+
+    /// The account which paid the RaffleEntry's rent, either the entry's payer or the Bakery Treasury.
+    /// CHECK: Verified as the entry's rent_payer, and only receives its rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// Ata of token_mint type owned by config authority wallet, holding the Raffle's prizes.
+    /// CHECK: Verified as the Bakery's delegated ata in the handler.
+    #[account(mut)]
+    pub token: UncheckedAccount<'info>,
+
+    /// Token account of token_mint type owned by the user, receiving the prize.
+    #[account(mut,
+              constraint = user_token.owner == user.key(),
+              constraint = user_token.mint == tag.token_mint)]
+    pub user_token: Box<Account<'info, TokenAccount>>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+}
+
+/// Hands a Raffle winner their prize, and closes their entry.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ClaimRafflePrize<'info>>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let tag = &mut ctx.accounts.tag;
+    let config_seeds = &[PDA_PREFIX, config.authority.as_ref(), &[config.bump]];
+
+    require!(tag.raffle_seed.is_some(), ErrorCode::RaffleNotDrawn);
+    require!(ctx.accounts.raffle_entry.is_winner(tag), ErrorCode::NotARaffleWinner);
+    assert_is_ata(
+        &ctx.accounts.token,
+        &config.authority,
+        &tag.token_mint,
+        Some(&config.key()),
+    )?;
+
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.token.to_account_info(),
+        to: ctx.accounts.user_token.to_account_info(),
+        authority: config.to_account_info(),
+    };
+    let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(context.with_signer(&[&config_seeds[..]]), tag.raffle_prize)?;
+
+    // Count the prize as a claim, so num_claimed tracks how many winners were paid out.
+    tag.num_claimed = tag.num_claimed
+        .checked_add(1)
+        .ok_or(ErrorCode::NumericalOverflowError)?;
+    tag.last_claimed_at = Clock::get()?.unix_timestamp;
+
+    Ok(())
+}
@@ -2,23 +2,29 @@ use std::str::FromStr;
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::instruction::Instruction;
 use anchor_lang::solana_program::program::{invoke_signed, invoke};
-use anchor_lang::solana_program::system_program;
-use anchor_spl::token::{self, Token};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::{system_instruction, system_program};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Token, TokenAccount};
 use mpl_token_auth_rules::payload::Payload;
 use mpl_token_metadata;
-use mpl_token_metadata::instruction::{
-    mint_new_edition_from_master_edition_via_token
-};
 use mpl_token_metadata::processor::AuthorizationData;
 use mpl_token_metadata::state::{Metadata, TokenMetadataAccount};
 use crate::errors::ErrorCode;
-use crate::state::{PDA_PREFIX};
-use crate::state::{bakery::*, sprinkle::*, user_info::*};
+use crate::state::{PDA_PREFIX, ESCROW, GATE, MINT, RAFFLE, REFERRAL, REFERRED, SESSION, TOKEN, TREASURY, VESTING};
+use crate::state::{bakery::*, bundle::*, campaign::*, claim_escrow::*, gate::*, quest::*, raffle::*, referral::*, session_key::*, sprinkle::*, treasury::*, user_info::*, vesting::*};
 use crate::utils::{
-    assert_is_ata, assert_keys_equal, 
-    sighash, grab_update_authority, 
-    get_master_edition_supply,
-    move_hot_potato, MoveHotPotatoArgs
+    assert_is_ata, assert_keys_equal, assert_owned_by, token_2022,
+    assert_derivation,
+    sighash, make_ata, make_ata_if_missing,
+    create_or_allocate_account_raw,
+    load_or_create_program_account, verify_merkle_proof,
+    uncreated_account_balances, created_accounts_rent,
+    make_program_mint, MakeProgramMintArgs,
+    move_hot_potato, MoveHotPotatoArgs,
+    deliver_sprinkle, DeliverSprinkleArgs, SprinkleDelivery,
+    begin_claim, BeginClaimArgs, record_claim, RecordClaimArgs,
+    run_claim_hook, has_ed25519_attestation
 };
 
 #[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq)]
@@ -30,9 +36,30 @@ pub struct CandyMachineArgs {
     creator_bump: u8,
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Default)]
+pub struct ClaimTagParams {
+    /// Merkle proof that the user is on the Sprinkle's allowlist. Ignored if the Sprinkle has no allowlist.
+    pub allowlist_proof: Vec<[u8; 32]>,
+
+    /// The user's allocation in their allowlist leaf, if it has one. Overrides the Sprinkle's per_user value.
+    pub allocation: Option<u64>,
+
+    /// The highest claim price the claimer accepts. Guards against the price rising before the claim lands.
+    pub max_price: Option<u64>,
+
+    /// Hash of the email or phone number to claim to, for users without a wallet.
+    /// If set, the user account must be the ClaimEscrow PDA of this identity, which receives the tokens.
+    pub identity_hash: Option<[u8; 32]>,
+
+    /// Unix timestamp after which the combo keys' attestations to this claim expire. Required for combo Sprinkles.
+    pub combo_expires_at: Option<i64>,
+}
+
 #[derive(Accounts)]
-pub struct ClaimTag<'info> {
+pub struct ClaimTagV2<'info> {
     /// Account which receives the NFT claimed from this Sprinkle.
+    /// For claims to an identity, this is the ClaimEscrow PDA with seed
+    /// [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), ESCROW, &identity_hash], and must be writable.
     /// CHECK: User can be any account that can sign a transaction.
     pub user: UncheckedAccount<'info>,
 
@@ -45,11 +72,14 @@ pub struct ClaimTag<'info> {
     pub config: Box<Account<'info, Config>>,
 
     /// Account which has the authority to execute claims for this Sprinkle.
+    /// Can be the tag_authority, any of the additional_authorities, a session key issued by the Bakery,
+    /// or the program_authority PDA, signing through invoke_signed from the Sprinkle's authority_program.
     pub tag_authority: Signer<'info>,
 
     /// PDA which stores data about the state of a Sprinkle.
     #[account(mut, 
-              has_one = tag_authority,
+              constraint = tag.is_tag_authority(&tag_authority.key())
+                  || session_key.is_some() @ ErrorCode::InvalidTagAuthority,
               seeds = [
                   PDA_PREFIX, 
                   config.authority.key().as_ref(), 
@@ -58,18 +88,143 @@ pub struct ClaimTag<'info> {
               bump = tag.bump)]
     pub tag: Box<Account<'info, Tag>>,
 
-    /// PDA which stores a counter of how many times this user has claimed this Sprinkle.
-    #[account(init_if_needed, 
-              payer = payer,
-              space = UserInfo::SIZE, 
+    /// PDA which stores a counter of how many times this user has claimed this Sprinkle, in its current season.
+    /// Seed [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), user.key().as_ref(), &tag.season_seed()].
+    /// CHECK: Derivation verified in the handler, which creates it on the user's first claim of the season,
+    /// and grows UserInfos created with an earlier, smaller layout.
+    #[account(mut)]
+    pub user_info: UncheckedAccount<'info>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+
+    /// SPL Rent Sysvar, required for account allocation.
+    pub rent: Sysvar<'info, Rent>,
+
+    /// PDA of the Quest this Sprinkle is part of. Required if the Sprinkle has a quest set.
+    pub quest: Option<Box<Account<'info, Quest>>>,
+
+    /// PDA which tracks the user's progress on the Quest, with seed
+    /// [PREFIX, config.authority.as_ref(), QUEST, &quest.id.to_le_bytes(), user.key().as_ref()].
+    /// Will be initialized if not setup.
+    /// CHECK: Derivation is verified in the handler.
+    #[account(mut)]
+    pub quest_progress: Option<UncheckedAccount<'info>>,
+
+    /// PDA of the Campaign this Sprinkle draws from. Required if the Sprinkle has a campaign set.
+    #[account(mut)]
+    pub campaign: Option<Box<Account<'info, Campaign>>>,
+
+    /// PDA which stores a counter of how many times this user has claimed from the Campaign, with seed
+    /// [PREFIX, config.authority.as_ref(), CAMPAIGN, &campaign.id.to_le_bytes(), user.key().as_ref()].
+    /// Will be initialized if not setup.
+    /// CHECK: Derivation is verified in the handler.
+    #[account(mut)]
+    pub campaign_user_info: Option<UncheckedAccount<'info>>,
+
+    /// PDA of the session key signing as tag_authority. Required if tag_authority is not an authority of the Sprinkle.
+    #[account(mut,
+              constraint = session_key.key == tag_authority.key() @ ErrorCode::InvalidTagAuthority,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  SESSION,
+                  tag_authority.key().as_ref()
+              ],
+              bump = session_key.bump)]
+    pub session_key: Option<Box<Account<'info, SessionKey>>>,
+
+    /// PDA which reimburses the payer for the rent costs of the claim, and pays referral rewards.
+    /// Only used if the Sprinkle is sponsored, or rewards referrals.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  TREASURY
+              ],
+              bump = treasury.bump)]
+    pub treasury: Option<Box<Account<'info, Treasury>>>,
+
+    /// SPL Associated Token Account Program, required to create the user's ATA if it is missing.
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// The mint of the token handed out by this Sprinkle, required to create the user's ATA if it is missing.
+    /// CHECK: Verified against the Sprinkle's token_mint in the handler.
+    pub token_mint: Option<UncheckedAccount<'info>>,
+
+    /// SPL Token-2022 Program, required to create the user's ATA if it is missing and token_mint is a Token-2022 mint,
+    /// and to transfer Token-2022 tokens.
+    /// CHECK: Verified by address.
+    #[account(address = token_2022::ID)]
+    pub token_2022_program: Option<UncheckedAccount<'info>>,
+
+    /// Account which referred the user to this claim. Rewarded from the Bakery Treasury
+    /// for the first referred claim of each user, if the Sprinkle rewards referrals.
+    /// CHECK: Can be any account other than the user.
+    #[account(mut)]
+    pub referrer: Option<UncheckedAccount<'info>>,
+
+    /// PDA which counts the rewarded referrals of the referrer, with seed
+    /// [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), REFERRAL, referrer.key().as_ref()].
+    /// Will be initialized if not setup.
+    /// CHECK: Derivation is verified in the handler.
+    #[account(mut)]
+    pub referrer_info: Option<UncheckedAccount<'info>>,
+
+    /// Ata of referral_reward_mint type owned by the Treasury. Required if referral rewards are paid in an SPL token.
+    #[account(mut)]
+    pub treasury_token: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Token account of referral_reward_mint type owned by the referrer. Required if referral rewards are paid in an SPL token.
+    #[account(mut)]
+    pub referrer_token: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// PDA which marks the user as referred, with seed
+    /// [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), REFERRED, user.key().as_ref()].
+    /// Required if the Sprinkle rewards referrals. Will be initialized when the user's referral is rewarded.
+    /// CHECK: Derivation is verified in the handler.
+    #[account(mut)]
+    pub referral_info: Option<UncheckedAccount<'info>>,
+}
+
+/// The accounts of the original claim_tag instruction, kept so existing clients keep working.
+/// Claims through it go through the same handler as claim_tag_v2, without any of its optional accounts.
+#[derive(Accounts)]
+pub struct ClaimTag<'info> {
+    /// Account which receives the NFT claimed from this Sprinkle.
+    /// CHECK: User can be any account that can sign a transaction.
+    pub user: UncheckedAccount<'info>,
+
+    /// Account which pays the network and rent fees, for this transaction only.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(mut)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// Account which has the authority to execute claims for this Sprinkle.
+    pub tag_authority: Signer<'info>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut, 
+              constraint = tag.is_tag_authority(&tag_authority.key()) @ ErrorCode::InvalidTagAuthority,
               seeds = [
                   PDA_PREFIX, 
-                  config.authority.as_ref(), 
-                  &tag.uid.to_le_bytes(), 
-                  user.key().as_ref()
+                  config.authority.key().as_ref(), 
+                  &tag.uid.to_le_bytes()
               ], 
-              bump)]
-    pub user_info: Box<Account<'info, UserInfo>>,
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// PDA which stores a counter of how many times this user has claimed this Sprinkle, in its current season.
+    /// CHECK: Derivation verified in the handler, which creates it on the user's first claim of the season.
+    #[account(mut)]
+    pub user_info: UncheckedAccount<'info>,
 
     /// SPL System Program, required for account allocation.
     pub system_program: Program<'info, System>,
@@ -82,9 +237,16 @@ pub struct ClaimTag<'info> {
 }
 
 // Remaining accounts - 
+    // CheckIn:
+        // none
+    //
+    // Raffle:
+        // raffle_entry (w) - RaffleEntry PDA with seed [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), RAFFLE, user.key().as_ref(), &tag.season_seed()]
+        // will be initialized, the user must not have entered yet.
+    //
     // SingleUse1Of1, Refillable1Of1, WalletRestrictedFungible:
         // token (w) - ata of token_mint type owned by config authority wallet
-        // user_ata (w) - ata of token_mint type for user
+        // user_ata (w) - ata of token_mint type for user, created if missing
         // If the underlying asset is Programmable, also:
             // bakery_auth
             // token_mint
@@ -106,22 +268,28 @@ pub struct ClaimTag<'info> {
         // token_mint - token mint on the tag
         // token (w) - ata of config authority containing the token
         // new_token_mint (w) - new token mint, must be supply = 1, decimals = 0
+        // or, to have the program create it, the uninitialized PDA with seed
+        // [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), MINT, &edition.to_le_bytes()]
         // new_metadata (w) - precomputed new metadata key(will be set by inner CPI here)
         // new_edition (w) - precomputed new edition key
         // metadata - existing metadata of current token_mint
         // master_edition (w) - existing master edition of current token_mint
         // edition_mark_pda (w) - What edition page this edition you are trying to mint is on
-        // new_mint_authority (s) - Authority of new mint
+        // new_mint_authority (s) - Authority of new mint, or config if the program creates the new mint
         // update_authority - Authority of metadata
         // token_metadata_program
+        // > Only needed if the program creates the new mint
+        // user_new_token (w) - ata of new_token_mint type for user, created along with the mint
     //
     // CandyMachineDrop:
         // candy_machine_id (w)
         // candy_machine_creator (pda of candy_machine [PREFIX.as_bytes(), candy_machine.key().as_ref()])
         // new_token_mint (w) - new token mint, must be supply = 1, decimals = 0
+        // or, to have the program create it, the uninitialized PDA with seed
+        // [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), MINT, &(tag.num_minted + 1).to_le_bytes()]
         // new_metadata (w) - precomputed new metadata key(will be set by inner CPI here)
         // new_edition (w) - precomputed new edition key
-        // new_mint_authority (s) - authority for freeze and mint on the mint object.
+        // new_mint_authority (s) - authority for freeze and mint on the mint object, or config if the program creates the new mint.
         // token_metadata_program
         // cmv2 program
         // clock sysvar
@@ -133,107 +301,478 @@ pub struct ClaimTag<'info> {
         // whitelist_token_mint (w)
         // > Only needed if candy machine has token mint
         // token_account_info (w) - either configs or yours depending on who pays
+        // > Only needed if the program creates the new mint
+        // user_new_token (w) - ata of new_token_mint type for user, created along with the mint
+    //
+    // WalletRestrictedFungible with a vesting schedule:
+        // token (w) - ata of token_mint type owned by config authority wallet
+        // vesting_escrow (w) - new VestingEscrow PDA with seed [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), user.key().as_ref(), VESTING, schedule_seed]
+        // where schedule_seed is tag.season.to_le_bytes() followed by the user's num_claimed in the season, as u64 le bytes.
+        // vesting_token (w) - escrow token account with seed [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), user.key().as_ref(), VESTING, schedule_seed, TOKEN]
+        // will be initialized along with the escrow.
+        // token_mint - token mint on the tag
+    //
+    // TradeIn:
+        // token (w) - ata of token_mint type owned by config authority wallet
+        // user_ata (w) - ata of token_mint type for user, created if missing
+        // input_token (w) - token account of the user holding the token being handed in
+        // input_mint (w) - mint of the token being handed in
+        // > Only needed if the tag accepts any verified member of a collection
+        // input_metadata - Metadata account of input_mint
+        // > Only needed if the input token is not burnt
+        // bakery_authority - config authority wallet
+        // bakery_input_token (w) - ata of input_mint type owned by config authority wallet, created if missing
+        // associated_token_program
+    //
+    // Bundle:
+        // bundle - Bundle PDA with seed [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), BUNDLE]
+        // for each slot of the bundle, in order:
+            // token (w) - ata of the slot's token_mint type owned by config authority wallet
+            // user_ata (w) - ata of the slot's token_mint type for user, created if missing
+            // token_mint - the slot's token_mint
+//
+// Combo Sprinkles, after all of the above:
+    // for each combo key, in order:
+        // combo_key - the key itself, either signing the transaction or attesting through an Ed25519 instruction
+    // instructions_sysvar
+//
+// Sprinkles with a claim hook, after all of the above:
+    // hook_program - the Sprinkle's claim_hook program
+    // hook_authority - PDA with seed [PREFIX, config.authority.as_ref(), &tag.uid.to_le_bytes(), HOOK], signs the hook call
+    // the num_claim_hook_accounts accounts the hook takes, in the order it expects them
+//
+// Sprinkles with a claim price, after all of the above:
+    // > Only needed if the price is paid in an SPL token
+    // price_token (w) - token account of claim_price_mint type owned by the payer (or user, if user_pays_price)
+    // for each revenue split, in order:
+        // recipient (w) - the recipient's wallet, or their token account of claim_price_mint type
+//
+// Gated Sprinkles of any type, after all of the above:
+    // gate_token (w) - token account of the user holding the gate token
+    // gate_mint (w) - mint of the gate token
+    // > Only needed if the gate accepts any verified member of a collection
+    // gate_metadata - Metadata account of gate_mint
+    // > Only needed if the gate is a Lock gate
+    // gate_lock (w) - GateLock PDA with seed [PREFIX, tag.key().as_ref(), GATE, gate_mint.key().as_ref()]
 // -
 
 pub fn handler<'a, 'b, 'c, 'info>(
-  ctx: Context<'a, 'b, 'c, 'info, ClaimTag<'info>>,
+  ctx: Context<'a, 'b, 'c, 'info, ClaimTagV2<'info>>,
   creator_bump: u8, // Ignored except in candy machine use and hotpotato use. In hotpotato is used to make the token account.
+  params: ClaimTagParams,
 ) -> Result<()> {   
+    // Track the accounts this claim may create, so a sponsored claim can reimburse their rent.
+    let uncreated_accounts = uncreated_account_balances(
+        ctx.accounts.to_account_infos().into_iter().chain(ctx.remaining_accounts.iter().cloned()),
+    );
+
     let tag = &mut ctx.accounts.tag;
     let tag_type: TagType = tag.tag_type;
     let config = &ctx.accounts.config;
     let payer = &ctx.accounts.payer;
-    let user_info = &ctx.accounts.user_info;
     let user = &ctx.accounts.user;
     let config_seeds = &[&PDA_PREFIX[..], &config.authority.as_ref()[..], &[config.bump]];
 
+    // Load the user's UserInfo for the current season, creating it on their first claim of the season.
+    let (mut user_info, user_info_bump) = load_or_create_program_account::<UserInfo>(
+        &ctx.accounts.user_info,
+        &payer.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.rent,
+        UserInfo::SIZE,
+        &[
+            PDA_PREFIX,
+            config.authority.as_ref(),
+            &tag.uid.to_le_bytes(),
+            user.key().as_ref(),
+            &tag.season_seed(),
+        ],
+    )?;
+    user_info.bump = user_info_bump;
+
     // Ensure the Sprinkle's total_supply value has not already been reached.
     // HotPotatos have no claim limits, so they are excluded from this check.
     // Sprinkles with a total_supply of 0 have unlimited claims, so they are excluded from this check.
+    // Bundles are only claimable while all of their slots are funded, so a supply of 0 means empty.
     if tag.tag_type != TagType::HotPotato
-        && (tag.total_supply > 0)
-        && (tag.num_claimed == tag.total_supply)
+        && (tag.total_supply > 0 || tag.tag_type == TagType::Bundle)
+        && (tag.num_claimed >= tag.total_supply)
     {
         return Err(ErrorCode::TagDepleted.into());
     };
 
-    // Ensure the claiming user has not reached the Sprinkle's per_user value.
+    // If the Sprinkle has an allowlist, ensure the user is on it.
+    // A leaf can carry an allocation, which takes the place of the Sprinkle's per_user value.
+    let per_user = match tag.allowlist_root {
+        Some(root) => {
+            let leaf = match params.allocation {
+                Some(allocation) => keccak::hashv(&[user.key().as_ref(), &allocation.to_le_bytes()]),
+                None => keccak::hashv(&[user.key().as_ref()]),
+            };
+            require!(
+                verify_merkle_proof(&params.allowlist_proof, &root, leaf.to_bytes()),
+                ErrorCode::NotOnAllowlist
+            );
+            params.allocation.unwrap_or(tag.per_user)
+        }
+        None => tag.per_user,
+    };
+
+    // Ensure the claiming user has not reached their per_user value.
     // HotPotatos have no claim limits, so they are excluded from this check.
     require!(
-        tag.tag_type == TagType::HotPotato || user_info.num_claimed < tag.per_user,
+        tag.tag_type == TagType::HotPotato || user_info.num_claimed < per_user,
         ErrorCode::ClaimLimitExceeded
     );
 
+    // Ensure the claiming user has not reached the Sprinkle's per_period value within their current period.
+    // A new period starts with the user's first claim after the previous one has elapsed.
+    // HotPotatos have no claim limits, so they are excluded from this check.
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        tag.tag_type == TagType::HotPotato
+            || tag.claim_period == 0
+            || user_info.current_period_claims(tag.claim_period, now) < tag.per_period,
+        ErrorCode::PeriodClaimLimitExceeded
+    );
+
+    // If the claim is signed by a session key rather than one of the Sprinkle's authorities,
+    // ensure the key covers this Sprinkle, has not expired, and spend one claim of its budget.
+    if !tag.is_tag_authority(&ctx.accounts.tag_authority.key()) {
+        let session_key = ctx.accounts.session_key.as_mut().ok_or(ErrorCode::InvalidTagAuthority)?;
+        require!(session_key.covers(tag.uid, tag.campaign), ErrorCode::SessionKeyOutOfScope);
+        require!(now < session_key.expires_at, ErrorCode::SessionKeyExpired);
+        session_key.claim_budget = session_key
+            .claim_budget
+            .checked_sub(1)
+            .ok_or(ErrorCode::SessionKeyBudgetExhausted)?;
+    }
+
     // Ensure that if the Sprinkle's minter_pays is set to true, 
     // the BakeryAuthority is not the one paying for the transaction fees.
     if tag.minter_pays {
         require!(config.authority != payer.key(), ErrorCode::AuthorityShouldNotBePayer);
     }
 
+    // Record the user's progress on the Sprinkle's Quest, and ensure the Campaign's limits have not been reached,
+    // capping fungible claims to what is left of the Campaign.
+    let (campaign_user_info, campaign_remaining) = begin_claim(BeginClaimArgs {
+        tag,
+        config,
+        user: user.key(),
+        payer: &payer.to_account_info(),
+        quest: ctx.accounts.quest.as_deref(),
+        quest_progress: ctx.accounts.quest_progress.as_deref(),
+        campaign: ctx.accounts.campaign.as_deref(),
+        campaign_user_info: ctx.accounts.campaign_user_info.as_deref(),
+        rent: &ctx.accounts.rent,
+        system_program: &ctx.accounts.system_program,
+    })?;
+
+    // If the Sprinkle is gated, ensure the user holds the gate token, passed at the end of the remaining accounts,
+    // then lock or burn it as required.
+    let num_gate_accounts = tag.num_gate_accounts();
+    if let Some(gate) = tag.gate {
+        require!(
+            ctx.remaining_accounts.len() >= num_gate_accounts,
+            ErrorCode::GateNotPassed
+        );
+        let gate_accounts = &ctx.remaining_accounts[ctx.remaining_accounts.len() - num_gate_accounts..];
+        let gate_token = &gate_accounts[0];
+        let gate_mint = &gate_accounts[1];
+        let mut ctr = 2;
+
+        let gate_token_account: Account<TokenAccount> = Account::try_from(gate_token)?;
+        assert_keys_equal(gate_token_account.owner, user.key())?;
+        assert_keys_equal(gate_token_account.mint, gate_mint.key())?;
+        require!(gate_token_account.amount >= gate.amount, ErrorCode::GateNotPassed);
+
+        // Ensure the gate token is the one required by the Sprinkle,
+        // or a verified member of the required collection.
+        if gate.is_collection {
+            let gate_metadata = &gate_accounts[ctr];
+            ctr += 1;
+            assert_owned_by(gate_metadata, &mpl_token_metadata::ID)?;
+            let metadata = Metadata::from_account_info(gate_metadata)?;
+            assert_keys_equal(metadata.mint, gate_mint.key())?;
+            match metadata.collection {
+                Some(collection) if collection.verified && collection.key == gate.mint => {}
+                _ => return Err(ErrorCode::GateNotPassed.into()),
+            }
+        } else {
+            assert_keys_equal(gate_mint.key(), gate.mint)?;
+        }
+
+        match gate.action {
+            GateAction::Hold => {}
+
+            GateAction::Lock => {
+                let (mut gate_lock, gate_lock_bump) = load_or_create_program_account::<GateLock>(
+                    &gate_accounts[ctr],
+                    &payer.to_account_info(),
+                    &ctx.accounts.system_program,
+                    &ctx.accounts.rent,
+                    GateLock::SIZE,
+                    &[
+                        PDA_PREFIX,
+                        tag.key().as_ref(),
+                        GATE,
+                        gate_mint.key().as_ref(),
+                    ],
+                )?;
+                require!(gate_lock.tag == Pubkey::default(), ErrorCode::GateTokenAlreadyUsed);
+                gate_lock.tag = tag.key();
+                gate_lock.user = user.key();
+                gate_lock.bump = gate_lock_bump;
+                gate_lock.exit(ctx.program_id)?;
+            }
+
+            GateAction::Burn => {
+                // The user is giving up their gate token, so they need to approve the claim.
+                require!(user.is_signer, ErrorCode::UserMustSign);
+                let cpi_accounts = token::Burn {
+                    mint: gate_mint.clone(),
+                    from: gate_token.clone(),
+                    authority: user.to_account_info(),
+                };
+                let context = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts
+                );
+                token::burn(context, gate.amount)?;
+            }
+        }
+    }
+
+    // If the Sprinkle has a claim price, collect it from the payer (or user) and split it between the
+    // Sprinkle's recipients. The price accounts are passed just before the gate accounts.
+    if tag.claim_price > 0 {
+        let num_price_accounts = tag.num_price_accounts();
+        let price_accounts_end = ctx.remaining_accounts
+            .len()
+            .checked_sub(num_gate_accounts)
+            .ok_or(ErrorCode::MissingClaimPriceAccounts)?;
+        let price_accounts = &ctx.remaining_accounts[price_accounts_end
+            .checked_sub(num_price_accounts)
+            .ok_or(ErrorCode::MissingClaimPriceAccounts)?..price_accounts_end];
+
+        let price_payer = if tag.user_pays_price {
+            require!(user.is_signer, ErrorCode::UserMustSign);
+            user.to_account_info()
+        } else {
+            payer.to_account_info()
+        };
+
+        // The price follows the Sprinkle's curve, and can't exceed what the claimer agreed to.
+        let claim_price = tag.current_claim_price()?;
+        if let Some(max_price) = params.max_price {
+            require!(claim_price <= max_price, ErrorCode::ClaimPriceTooHigh);
+        }
+        let shares = tag.revenue_shares(claim_price)?;
+
+        match tag.claim_price_mint {
+            None => {
+                for ((split, share), recipient) in tag.revenue_splits.iter().zip(shares).zip(price_accounts) {
+                    assert_keys_equal(recipient.key(), split.recipient)?;
+                    if share == 0 {
+                        continue;
+                    }
+                    invoke(
+                        &system_instruction::transfer(&price_payer.key(), &recipient.key(), share),
+                        &[
+                            price_payer.clone(),
+                            recipient.clone(),
+                            ctx.accounts.system_program.to_account_info(),
+                        ],
+                    )?;
+                }
+            }
+
+            Some(price_mint) => {
+                let price_token = &price_accounts[0];
+                let price_token_account: Account<TokenAccount> = Account::try_from(price_token)?;
+                assert_keys_equal(price_token_account.owner, price_payer.key())?;
+                assert_keys_equal(price_token_account.mint, price_mint)?;
+
+                for ((split, share), recipient_token) in tag.revenue_splits.iter().zip(shares).zip(&price_accounts[1..]) {
+                    let recipient_token_account: Account<TokenAccount> = Account::try_from(recipient_token)?;
+                    assert_keys_equal(recipient_token_account.owner, split.recipient)?;
+                    assert_keys_equal(recipient_token_account.mint, price_mint)?;
+                    if share == 0 {
+                        continue;
+                    }
+                    let cpi_accounts = token::Transfer {
+                        from: price_token.clone(),
+                        to: recipient_token.clone(),
+                        authority: price_payer.clone(),
+                    };
+                    let context = CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        cpi_accounts
+                    );
+                    token::transfer(context, share)?;
+                }
+            }
+        }
+    }
+
+    // For claims to an identity, the tokens go to the identity's ClaimEscrow, which stands in as the user.
+    // Only Sprinkles handing out plain SPL tokens can be claimed this way.
+    let mut claim_escrow = None;
+    let mut new_claim_escrow = false;
+    if let Some(identity_hash) = params.identity_hash {
+        require!(
+            tag.escrow_duration > 0
+                && tag.vesting_duration == 0
+                && matches!(
+                    tag_type,
+                    TagType::SingleUse1Of1 | TagType::Refillable1Of1 | TagType::WalletRestrictedFungible
+                ),
+            ErrorCode::IdentityClaimNotSupported
+        );
+        require!(user.is_writable, ErrorCode::ClaimEscrowNotWritable);
+
+        let (mut escrow, escrow_bump) = load_or_create_program_account::<ClaimEscrow>(
+            user,
+            &payer.to_account_info(),
+            &ctx.accounts.system_program,
+            &ctx.accounts.rent,
+            ClaimEscrow::SIZE,
+            &[
+                PDA_PREFIX,
+                config.authority.as_ref(),
+                &tag.uid.to_le_bytes(),
+                ESCROW,
+                &identity_hash,
+            ],
+        )?;
+        if escrow.tag == Pubkey::default() {
+            escrow.tag = tag.key();
+            escrow.identity_hash = identity_hash;
+            escrow.bump = escrow_bump;
+            escrow.rent_payer = payer.key();
+            new_claim_escrow = true;
+        }
+        require!(
+            escrow.amount == 0 || escrow.token_mint == tag.token_mint,
+            ErrorCode::ClaimEscrowMintMismatch
+        );
+        escrow.token_mint = tag.token_mint;
+        escrow.expires_at = now
+            .checked_add(tag.escrow_duration)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+        claim_escrow = Some(escrow);
+    }
+
+    // If the Sprinkle is a combo, ensure every combo key attested to this claim, either by signing
+    // the transaction or through an Ed25519 signature over the combo message.
+    // The combo accounts are passed just before the hook accounts.
+    if !tag.combo_keys.is_empty() {
+        let combo_accounts_end = ctx.remaining_accounts
+            .len()
+            .checked_sub(tag.num_hook_accounts() + tag.num_price_accounts() + num_gate_accounts)
+            .ok_or(ErrorCode::MissingComboAccounts)?;
+        let combo_accounts = &ctx.remaining_accounts[combo_accounts_end
+            .checked_sub(tag.num_combo_accounts())
+            .ok_or(ErrorCode::MissingComboAccounts)?..combo_accounts_end];
+        let instructions_sysvar = &combo_accounts[tag.combo_keys.len()];
+        assert_keys_equal(instructions_sysvar.key(), anchor_lang::solana_program::sysvar::instructions::ID)?;
+
+        let combo_expires_at = params.combo_expires_at.ok_or(ErrorCode::ComboAttestationExpired)?;
+        require!(now <= combo_expires_at, ErrorCode::ComboAttestationExpired);
+        let message = Tag::combo_message(&tag.key(), &user.key(), tag.season, user_info.num_claimed, combo_expires_at);
+        for (combo_key, combo_account) in tag.combo_keys.iter().zip(combo_accounts) {
+            assert_keys_equal(combo_account.key(), *combo_key)?;
+            require!(
+                combo_account.is_signer
+                    || has_ed25519_attestation(instructions_sysvar, combo_key, &message)?,
+                ErrorCode::ComboAttestationMissing
+            );
+        }
+    }
+
+    // The accounts used by the claim method itself, without the combo, hook, price and gate accounts passed after them.
+    let num_claim_accounts = ctx.remaining_accounts
+        .len()
+        .saturating_sub(
+            tag.num_combo_accounts() + tag.num_hook_accounts() + tag.num_price_accounts() + num_gate_accounts
+        );
+
     let mut amount_to_claim = 1;
+    let mut new_raffle_entry = None;
+    let mut new_vesting_escrow = None;
 
     match tag_type {
-        TagType::LimitedOrOpenEdition => {
-            let token_mint = &ctx.remaining_accounts[0];
-            let token = &ctx.remaining_accounts[1];
-            let new_token_mint = &ctx.remaining_accounts[2];
-            let new_metadata = &ctx.remaining_accounts[3];
-            let new_edition = &ctx.remaining_accounts[4];
-            let metadata = &ctx.remaining_accounts[5];
-            let master_edition = &ctx.remaining_accounts[6];
-            let edition_mark_pda = &ctx.remaining_accounts[7];
-            let new_mint_authority = &ctx.remaining_accounts[8];
-            let update_authority = &ctx.remaining_accounts[9];
-            let token_metadata_program = &ctx.remaining_accounts[10];
-
-            // Make sure that the provided metadata accounts are legitimate.
-            let update_auth = grab_update_authority(&metadata)?;
-            assert_keys_equal(update_auth, update_authority.key())?;
-            assert_keys_equal(tag.token_mint, token_mint.key())?;
-            assert_keys_equal(
-                token_metadata_program.key(),
-                mpl_token_metadata::ID,
-            )?;
+        // CheckIns move no assets, the claim is only recorded in the counters below.
+        TagType::CheckIn => {}
 
-            // Grab the MasterEdition supply, and incremment it to get the new Edition number.
-            let edition = get_master_edition_supply(&master_edition)?
-                .checked_add(1)
-                .ok_or(ErrorCode::NumericalOverflowError)?;
+        // Raffle scans only register the user's entry, until the window closes and the Raffle is drawn.
+        // Entries don't count as claims, winners are counted as they claim their prize.
+        TagType::Raffle => {
+            require!(now < tag.raffle_ends_at, ErrorCode::RaffleClosed);
+            let raffle_entry_info = &ctx.remaining_accounts[0];
+            require!(raffle_entry_info.data_is_empty(), ErrorCode::AlreadyEnteredRaffle);
 
-            // CPI into the Token Metadata Program to print a new Edition from the MasterEdition.
-            invoke(
-                &mint_new_edition_from_master_edition_via_token(
-                    token_metadata_program.key(),
-                    new_metadata.key(),
-                    new_edition.key(),
-                    master_edition.key(),
-                    new_token_mint.key(),
-                    new_mint_authority.key(),
-                    payer.key(),
-                    config.authority,
-                    token.key(),
-                    update_auth,
-                    metadata.key(),
-                    token_mint.key(),
-                    edition,
-                ),
+            let (mut raffle_entry, raffle_entry_bump) = load_or_create_program_account::<RaffleEntry>(
+                raffle_entry_info,
+                &payer.to_account_info(),
+                &ctx.accounts.system_program,
+                &ctx.accounts.rent,
+                RaffleEntry::SIZE,
                 &[
-                    token_metadata_program.clone(),
-                    new_metadata.clone(),
-                    new_edition.clone(),
-                    master_edition.clone(),
-                    new_token_mint.clone(),
-                    new_mint_authority.clone(),
-                    payer.to_account_info(),
-                    config.to_account_info(),
-                    token.clone(),
-                    update_authority.clone(),
-                    metadata.clone(),
-                    token_mint.clone(),
-                    edition_mark_pda.clone(),
-                    ctx.accounts.rent.to_account_info(),
+                    PDA_PREFIX,
+                    config.authority.as_ref(),
+                    &tag.uid.to_le_bytes(),
+                    RAFFLE,
+                    user.key().as_ref(),
+                    &tag.season_seed(),
                 ],
             )?;
+            raffle_entry.tag = tag.key();
+            raffle_entry.user = user.key();
+            raffle_entry.index = tag.num_entries;
+            raffle_entry.season = tag.season;
+            raffle_entry.bump = raffle_entry_bump;
+            raffle_entry.rent_payer = payer.key();
+            new_raffle_entry = Some(raffle_entry);
+
+            tag.num_entries = tag.num_entries
+                .checked_add(1)
+                .ok_or(ErrorCode::NumericalOverflowError)?;
+            amount_to_claim = 0;
+        }
+
+        TagType::LimitedOrOpenEdition => {
+            deliver_sprinkle(DeliverSprinkleArgs {
+                delivery: SprinkleDelivery::Edition {
+                    token_mint: &ctx.remaining_accounts[0],
+                    token: &ctx.remaining_accounts[1],
+                    new_token_mint: &ctx.remaining_accounts[2],
+                    new_metadata: &ctx.remaining_accounts[3],
+                    new_edition: &ctx.remaining_accounts[4],
+                    metadata: &ctx.remaining_accounts[5],
+                    master_edition: &ctx.remaining_accounts[6],
+                    edition_mark_pda: &ctx.remaining_accounts[7],
+                    new_mint_authority: &ctx.remaining_accounts[8],
+                    update_authority: &ctx.remaining_accounts[9],
+                    token_metadata_program: &ctx.remaining_accounts[10],
+                    user_new_token: ctx.remaining_accounts.get(11),
+                },
+                tag,
+                config,
+                user: &user.to_account_info(),
+                payer: &payer.to_account_info(),
+                ata_program: ctx.accounts.associated_token_program
+                    .as_ref()
+                    .map(|program| program.to_account_info()),
+                rent: &ctx.accounts.rent,
+                system_program: &ctx.accounts.system_program,
+                token_program: &ctx.accounts.token_program,
+                token_2022_program: ctx.accounts.token_2022_program
+                    .as_ref()
+                    .map(|program| program.to_account_info()),
+                config_seeds,
+            })?;
         }
 
         TagType::CandyMachineDrop => {
@@ -326,6 +865,8 @@ pub fn handler<'a, 'b, 'c, 'info>(
                 keys.push(AccountMeta::new(token_account.key(), false));
                 accounts.push(token_account.clone());
 
+                ctr += 1;
+
                 if !tag.minter_pays {
                     // use config pda delegate for tfer
                     keys.push(AccountMeta::new_readonly(config.key(), true));
@@ -335,6 +876,44 @@ pub fn handler<'a, 'b, 'c, 'info>(
                 }
             }
 
+            // If the caller has not created the new mint, create it as a PDA of the Sprinkle and the number
+            // of mints created for it so far, with the Bakery PDA as mint authority.
+            if new_token_mint.data_is_empty() {
+                let user_new_token = &ctx.remaining_accounts[ctr];
+                let mint_number = tag.num_minted
+                    .checked_add(1)
+                    .ok_or(ErrorCode::NumericalOverflowError)?;
+                let uid = tag.uid.to_le_bytes();
+                let mint_number_bytes = mint_number.to_le_bytes();
+                let mint_bump = assert_derivation(
+                    ctx.program_id,
+                    new_token_mint,
+                    &[PDA_PREFIX, config.authority.as_ref(), &uid, MINT, &mint_number_bytes],
+                )?;
+                require!(new_mint_authority.key() == config.key(), ErrorCode::InvalidNewMintAuthority);
+
+                make_program_mint(MakeProgramMintArgs {
+                    new_token_mint,
+                    user_token: user_new_token,
+                    user: &user.to_account_info(),
+                    config,
+                    payer: &payer.to_account_info(),
+                    ata_program: ctx.accounts.associated_token_program
+                        .as_ref()
+                        .ok_or(ErrorCode::MissingAtaAccounts)?
+                        .to_account_info(),
+                    rent: &ctx.accounts.rent,
+                    system_program: &ctx.accounts.system_program,
+                    token_program: &ctx.accounts.token_program,
+                    mint_seeds: &[PDA_PREFIX, config.authority.as_ref(), &uid, MINT, &mint_number_bytes, &[mint_bump]],
+                    config_seeds,
+                })?;
+                tag.num_minted = mint_number;
+
+                // The Bakery PDA signs the CandyMachine CPI as the new mint authority.
+                accounts.push(config.to_account_info());
+            }
+
             // Mint one NFT from the CandyMachine, to the claimer's wallet.
             invoke_signed(
                 &Instruction {
@@ -350,6 +929,120 @@ pub fn handler<'a, 'b, 'c, 'info>(
             )?;
         }
 
+        TagType::WalletRestrictedFungible if tag.vesting_duration > 0 => {
+            let token = &ctx.remaining_accounts[0];
+            let vesting_escrow_info = &ctx.remaining_accounts[1];
+            let vesting_token = &ctx.remaining_accounts[2];
+            let token_mint = &ctx.remaining_accounts[3];
+
+            // Ensure the Bakery ATA and token mint are legitimate.
+            assert_keys_equal(token_mint.key(), tag.token_mint)?;
+            assert_is_ata(
+                token,
+                &config.authority,
+                &tag.token_mint,
+                Some(&config.key()),
+            )?;
+
+            amount_to_claim = std::cmp::min(
+                tag.fungible_claim_amount(per_user, user_info.num_claimed)?,
+                campaign_remaining,
+            );
+
+            // Create a VestingEscrow for this claim, starting its own schedule with the Sprinkle's current settings.
+            let user_key = user.key();
+            let uid = tag.uid.to_le_bytes();
+            let schedule_seed = VestingEscrow::schedule_seed(tag.season, user_info.num_claimed);
+            let vesting_seeds = [
+                PDA_PREFIX,
+                config.authority.as_ref(),
+                &uid[..],
+                user_key.as_ref(),
+                VESTING,
+                &schedule_seed[..],
+            ];
+            let (mut vesting_escrow, vesting_bump) = load_or_create_program_account::<VestingEscrow>(
+                vesting_escrow_info,
+                &payer.to_account_info(),
+                &ctx.accounts.system_program,
+                &ctx.accounts.rent,
+                VestingEscrow::SIZE,
+                &vesting_seeds,
+            )?;
+            require!(vesting_escrow.tag == Pubkey::default(), ErrorCode::VestingScheduleStarted);
+
+            let token_seeds = [
+                PDA_PREFIX,
+                config.authority.as_ref(),
+                &uid[..],
+                user_key.as_ref(),
+                VESTING,
+                &schedule_seed[..],
+                TOKEN,
+            ];
+            let token_bump = assert_derivation(ctx.program_id, vesting_token, &token_seeds)?;
+            vesting_escrow.tag = tag.key();
+            vesting_escrow.user = user_key;
+            vesting_escrow.token_mint = tag.token_mint;
+            vesting_escrow.season = tag.season;
+            vesting_escrow.claim_offset = user_info.num_claimed;
+            vesting_escrow.start_ts = Clock::get()?.unix_timestamp;
+            vesting_escrow.cliff = tag.vesting_cliff;
+            vesting_escrow.duration = tag.vesting_duration;
+            vesting_escrow.bump = vesting_bump;
+            vesting_escrow.token_bump = token_bump;
+            vesting_escrow.rent_payer = payer.key();
+
+            // Initialize the escrow token account, owned by the VestingEscrow PDA.
+            create_or_allocate_account_raw(
+                ctx.accounts.token_program.key(),
+                vesting_token,
+                &ctx.accounts.rent,
+                &ctx.accounts.system_program,
+                payer,
+                TokenAccount::LEN,
+                &[
+                    PDA_PREFIX,
+                    config.authority.as_ref(),
+                    &uid[..],
+                    user_key.as_ref(),
+                    VESTING,
+                    &schedule_seed[..],
+                    TOKEN,
+                    &[token_bump],
+                ],
+            )?;
+            let cpi_accounts = token::InitializeAccount {
+                account: vesting_token.clone(),
+                mint: token_mint.clone(),
+                authority: vesting_escrow_info.clone(),
+                rent: ctx.accounts.rent.to_account_info(),
+            };
+            let context = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts
+            );
+            token::initialize_account(context)?;
+
+            // Move the claimed tokens into escrow, rather than to the user directly.
+            let cpi_accounts = token::Transfer {
+                from: token.clone(),
+                to: vesting_token.clone(),
+                authority: config.to_account_info(),
+            };
+            let context = CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts
+            );
+            token::transfer(
+                context.with_signer(&[&config_seeds[..]]),
+                amount_to_claim
+            )?;
+
+            vesting_escrow.total_amount = amount_to_claim;
+            new_vesting_escrow = Some(vesting_escrow);
+        }
+
         TagType::WalletRestrictedFungible
         | TagType::Refillable1Of1
         | TagType::SingleUse1Of1
@@ -358,43 +1051,50 @@ pub fn handler<'a, 'b, 'c, 'info>(
             let user_ata = &ctx.remaining_accounts[1];
 
             let mut is_programmable = false;
-            if ctx.remaining_accounts.len() > 2 {
+            if num_claim_accounts > 2 {
                 let token_metadata = Metadata::from_account_info(&ctx.remaining_accounts[4])?;
                 is_programmable = token_metadata.programmable_config != None;
             }
 
-            // Ensure both the Bakery and User ATAs are legitimate.
-            assert_is_ata(
-                &token,
-                &ctx.accounts.config.authority,
-                &tag.token_mint,
-                Some(&ctx.accounts.config.key()),
-            )?;
-            assert_is_ata(
-                &user_ata,
-                &ctx.accounts.user.key(),
-                &tag.token_mint,
-                Some(&ctx.accounts.config.key()),
-            )?;
-
-            // Calculate the maximum number of tokens the user
-            // can claim, without exceeding the per_user value.
-            amount_to_claim = tag
-                .per_user
-                .checked_sub(ctx.accounts.user_info.num_claimed)
-                .ok_or(ErrorCode::NumericalOverflowError)?;
-
-            // Now, take the minimum of that value,
-            // and the remaining supply in the Sprinkle.
+            // Calculate the maximum number of tokens the user can claim, without
+            // exceeding the per_user value or the remaining supply in the Sprinkle.
             amount_to_claim = std::cmp::min(
-                amount_to_claim,
-                tag.total_supply
-                    .checked_sub(tag.num_claimed)
-                    .ok_or(ErrorCode::NumericalOverflowError)?,
+                tag.fungible_claim_amount(per_user, user_info.num_claimed)?,
+                campaign_remaining,
             );
 
             match is_programmable {
                 true => {
+                    // Ensure both the Bakery and User ATAs are legitimate, creating the User ATA if needed.
+                    make_ata_if_missing(
+                        user_ata,
+                        user.to_account_info(),
+                        ctx.accounts.token_mint.as_ref().map(|mint| mint.to_account_info()),
+                        &tag.token_mint,
+                        payer.to_account_info(),
+                        ctx.accounts.associated_token_program
+                            .as_ref()
+                            .map(|program| program.to_account_info()),
+                        ctx.accounts.token_program.to_account_info(),
+                        ctx.accounts.token_2022_program
+                            .as_ref()
+                            .map(|program| program.to_account_info()),
+                        ctx.accounts.system_program.to_account_info(),
+                        ctx.accounts.rent.to_account_info(),
+                    )?;
+                    assert_is_ata(
+                        token,
+                        &ctx.accounts.config.authority,
+                        &tag.token_mint,
+                        Some(&ctx.accounts.config.key()),
+                    )?;
+                    assert_is_ata(
+                        user_ata,
+                        &ctx.accounts.user.key(),
+                        &tag.token_mint,
+                        Some(&ctx.accounts.config.key()),
+                    )?;
+
                     let bakery_authority = &ctx.remaining_accounts[2];
                     let token_mint = &ctx.remaining_accounts[3];
                     let token_metadata_info = &ctx.remaining_accounts[4];
@@ -466,21 +1166,178 @@ pub fn handler<'a, 'b, 'c, 'info>(
                     )?
                 },
 
-                false => {
-                    let cpi_accounts = token::Transfer {
-                        from: token.clone(),
-                        to: user_ata.clone(),
-                        authority: ctx.accounts.config.to_account_info(),
-                    };
-                    let context = CpiContext::new(
-                        ctx.accounts.token_program.to_account_info(), 
-                        cpi_accounts
-                    );
-                    token::transfer(
-                        context.with_signer(&[&config_seeds[..]]), 
-                        amount_to_claim
-                    )?
+                false => deliver_sprinkle(DeliverSprinkleArgs {
+                    delivery: SprinkleDelivery::Tokens {
+                        token,
+                        user_ata,
+                        token_mint: ctx.accounts.token_mint.as_ref().map(|mint| mint.to_account_info()),
+                        expected_mint: tag.token_mint,
+                        amount: amount_to_claim,
+                    },
+                    tag,
+                    config,
+                    user: &user.to_account_info(),
+                    payer: &payer.to_account_info(),
+                    ata_program: ctx.accounts.associated_token_program
+                        .as_ref()
+                        .map(|program| program.to_account_info()),
+                    rent: &ctx.accounts.rent,
+                    system_program: &ctx.accounts.system_program,
+                    token_program: &ctx.accounts.token_program,
+                    token_2022_program: ctx.accounts.token_2022_program
+                        .as_ref()
+                        .map(|program| program.to_account_info()),
+                    config_seeds,
+                })?,
+            }
+        }
+
+        TagType::TradeIn => {
+            let token = &ctx.remaining_accounts[0];
+            let user_ata = &ctx.remaining_accounts[1];
+            let input_token = &ctx.remaining_accounts[2];
+            let input_mint = &ctx.remaining_accounts[3];
+            let mut ctr = 4;
+
+            // The user is handing something in, so they need to approve the claim.
+            require!(user.is_signer, ErrorCode::UserMustSign);
+
+            // Ensure the user actually holds enough of the token being handed in.
+            let input_token_account: Account<TokenAccount> = Account::try_from(input_token)?;
+            assert_keys_equal(input_token_account.owner, user.key())?;
+            assert_keys_equal(input_token_account.mint, input_mint.key())?;
+            require!(
+                input_token_account.amount >= tag.input_amount,
+                ErrorCode::NotEnoughTradeInTokens
+            );
+
+            // Ensure the token being handed in is the one required by the Sprinkle,
+            // or a verified member of the required collection.
+            if tag.input_is_collection {
+                let input_metadata = &ctx.remaining_accounts[ctr];
+                ctr += 1;
+                assert_owned_by(input_metadata, &mpl_token_metadata::ID)?;
+                let metadata = Metadata::from_account_info(input_metadata)?;
+                assert_keys_equal(metadata.mint, input_mint.key())?;
+                match metadata.collection {
+                    Some(collection) if collection.verified && collection.key == tag.input_mint => {}
+                    _ => return Err(ErrorCode::InvalidTradeInToken.into()),
                 }
+            } else {
+                assert_keys_equal(input_mint.key(), tag.input_mint)?;
+            }
+
+            // Pull the input tokens from the user, before releasing anything.
+            if tag.input_burn {
+                let cpi_accounts = token::Burn {
+                    mint: input_mint.clone(),
+                    from: input_token.clone(),
+                    authority: user.to_account_info(),
+                };
+                let context = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts
+                );
+                token::burn(context, tag.input_amount)?;
+            } else {
+                let bakery_authority = &ctx.remaining_accounts[ctr];
+                let bakery_input_token = &ctx.remaining_accounts[ctr + 1];
+                let associated_token_program = &ctx.remaining_accounts[ctr + 2];
+                assert_keys_equal(bakery_authority.key(), config.authority)?;
+                assert_keys_equal(associated_token_program.key(), spl_associated_token_account::ID)?;
+                if bakery_input_token.data_is_empty() {
+                    make_ata(
+                        bakery_input_token.clone(),
+                        bakery_authority.clone(),
+                        input_mint.clone(),
+                        payer.to_account_info(),
+                        associated_token_program.clone(),
+                        ctx.accounts.token_program.to_account_info(),
+                        ctx.accounts.system_program.to_account_info(),
+                        ctx.accounts.rent.to_account_info(),
+                        &[],
+                    )?;
+                }
+                assert_is_ata(
+                    bakery_input_token,
+                    &config.authority,
+                    &input_mint.key(),
+                    Some(&config.key()),
+                )?;
+
+                let cpi_accounts = token::Transfer {
+                    from: input_token.clone(),
+                    to: bakery_input_token.clone(),
+                    authority: user.to_account_info(),
+                };
+                let context = CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts
+                );
+                token::transfer(context, tag.input_amount)?;
+            }
+
+            // Now release the reward to the user.
+            deliver_sprinkle(DeliverSprinkleArgs {
+                delivery: SprinkleDelivery::Tokens {
+                    token,
+                    user_ata,
+                    token_mint: ctx.accounts.token_mint.as_ref().map(|mint| mint.to_account_info()),
+                    expected_mint: tag.token_mint,
+                    amount: amount_to_claim,
+                },
+                tag,
+                config,
+                user: &user.to_account_info(),
+                payer: &payer.to_account_info(),
+                ata_program: ctx.accounts.associated_token_program
+                    .as_ref()
+                    .map(|program| program.to_account_info()),
+                rent: &ctx.accounts.rent,
+                system_program: &ctx.accounts.system_program,
+                token_program: &ctx.accounts.token_program,
+                token_2022_program: ctx.accounts.token_2022_program
+                    .as_ref()
+                    .map(|program| program.to_account_info()),
+                config_seeds,
+            })?;
+        }
+
+        TagType::Bundle => {
+            let bundle: Account<Bundle> = Account::try_from(&ctx.remaining_accounts[0])?;
+
+            // Ensure the provided Bundle belongs to this Sprinkle, and that every slot's accounts are present.
+            assert_keys_equal(bundle.tag, tag.key())?;
+            require!(
+                num_claim_accounts == 1 + 3 * bundle.slots.len(),
+                ErrorCode::InvalidBundleSlot
+            );
+
+            // Transfer every slot's assets in one go, so the claim either hands out all of them or none.
+            for (i, slot) in bundle.slots.iter().enumerate() {
+                deliver_sprinkle(DeliverSprinkleArgs {
+                    delivery: SprinkleDelivery::Tokens {
+                        token: &ctx.remaining_accounts[1 + 3 * i],
+                        user_ata: &ctx.remaining_accounts[2 + 3 * i],
+                        token_mint: Some(ctx.remaining_accounts[3 + 3 * i].clone()),
+                        expected_mint: slot.token_mint,
+                        amount: slot.amount,
+                    },
+                    tag,
+                    config,
+                    user: &user.to_account_info(),
+                    payer: &payer.to_account_info(),
+                    ata_program: ctx.accounts.associated_token_program
+                        .as_ref()
+                        .map(|program| program.to_account_info()),
+                    rent: &ctx.accounts.rent,
+                    system_program: &ctx.accounts.system_program,
+                    token_program: &ctx.accounts.token_program,
+                    token_2022_program: ctx.accounts.token_2022_program
+                        .as_ref()
+                        .map(|program| program.to_account_info()),
+                    config_seeds,
+                })?;
             }
         }
 
@@ -521,21 +1378,267 @@ pub fn handler<'a, 'b, 'c, 'info>(
         }
     };
 
-    // Increment the num_claimed counter in the claimer's UserInfoPDA.
-    ctx.accounts.user_info.num_claimed = ctx
-        .accounts
-        .user_info
-        .num_claimed
-        .checked_add(amount_to_claim)
-        .ok_or(ErrorCode::NumericalOverflowError)?;
+    // Record the tokens now held by the identity's ClaimEscrow.
+    if let Some(escrow) = claim_escrow.as_mut() {
+        escrow.amount = escrow.amount
+            .checked_add(amount_to_claim)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+    }
+
+    // Count the claim towards the claimer's UserInfoPDA, current period and Campaign.
+    record_claim(RecordClaimArgs {
+        tag: &ctx.accounts.tag,
+        user_info: &mut user_info,
+        campaign: ctx.accounts.campaign.as_deref_mut(),
+        campaign_user_info,
+        amount: amount_to_claim,
+        now,
+    })?;
 
     // Increment the num_claimed counter in the SprinklePDA.
+    let claim_index = ctx.accounts.tag.num_claimed;
     ctx.accounts.tag.num_claimed = ctx
         .accounts
         .tag
         .num_claimed
         .checked_add(amount_to_claim)
         .unwrap();
+    ctx.accounts.tag.last_claimed_at = now;
+
+    // If the Sprinkle rewards referrals, pay the referrer of the user's first referred claim from the Bakery Treasury.
+    // The user's ReferralInfo records the payout, so later claims of the same user, in any season,
+    // and referrers referring themselves, are not rewarded.
+    if let Some(referrer) = &ctx.accounts.referrer {
+        let tag = &mut ctx.accounts.tag;
+        let reward = tag.referral_reward;
+        if reward > 0
+            && tag.referral_spent.saturating_add(reward) <= tag.referral_budget
+            && ctx.accounts.referral_info
+                .as_ref()
+                .ok_or(ErrorCode::MissingReferralAccounts)?
+                .data_is_empty()
+        {
+            require!(referrer.key() != ctx.accounts.user.key(), ErrorCode::SelfReferral);
+            let treasury = ctx.accounts.treasury.as_ref().ok_or(ErrorCode::MissingReferralAccounts)?;
+            let referrer_info_info = ctx.accounts.referrer_info.as_ref().ok_or(ErrorCode::MissingReferralAccounts)?;
+            let referral_info_info = ctx.accounts.referral_info.as_ref().ok_or(ErrorCode::MissingReferralAccounts)?;
+
+            let (mut referrer_info, referrer_info_bump) = load_or_create_program_account::<ReferrerInfo>(
+                referrer_info_info,
+                &ctx.accounts.payer.to_account_info(),
+                &ctx.accounts.system_program,
+                &ctx.accounts.rent,
+                ReferrerInfo::SIZE,
+                &[
+                    PDA_PREFIX,
+                    config.authority.as_ref(),
+                    &tag.uid.to_le_bytes(),
+                    REFERRAL,
+                    referrer.key().as_ref(),
+                ],
+            )?;
+            require!(
+                tag.per_referrer_cap == 0 || referrer_info.num_referrals < tag.per_referrer_cap,
+                ErrorCode::ReferrerCapReached
+            );
+
+            match tag.referral_reward_mint {
+                // SOL rewards are moved straight out of the Treasury, which stays rent exempt.
+                None => {
+                    let treasury_info = treasury.to_account_info();
+                    require!(
+                        reward <= Treasury::available_lamports(&treasury_info)?,
+                        ErrorCode::InsufficientTreasuryFunds
+                    );
+                    **treasury_info.try_borrow_mut_lamports()? -= reward;
+                    **referrer.try_borrow_mut_lamports()? += reward;
+                }
+
+                // SPL rewards are transferred out of the Treasury's ata, signed by the Treasury PDA.
+                Some(reward_mint) => {
+                    let treasury_token = ctx.accounts.treasury_token.as_ref().ok_or(ErrorCode::MissingReferralAccounts)?;
+                    let referrer_token = ctx.accounts.referrer_token.as_ref().ok_or(ErrorCode::MissingReferralAccounts)?;
+                    assert_is_ata(&treasury_token.to_account_info(), &treasury.key(), &reward_mint, None)?;
+                    assert_keys_equal(referrer_token.owner, referrer.key())?;
+                    require!(
+                        treasury_token.amount >= reward,
+                        ErrorCode::InsufficientTreasuryFunds
+                    );
+
+                    let treasury_seeds = &[
+                        PDA_PREFIX,
+                        config.authority.as_ref(),
+                        TREASURY,
+                        &[treasury.bump],
+                    ];
+                    let cpi_accounts = token::Transfer {
+                        from: treasury_token.to_account_info(),
+                        to: referrer_token.to_account_info(),
+                        authority: treasury.to_account_info(),
+                    };
+                    let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+                    token::transfer(context.with_signer(&[&treasury_seeds[..]]), reward)?;
+                }
+            }
+
+            referrer_info.tag = tag.key();
+            referrer_info.referrer = referrer.key();
+            referrer_info.num_referrals = referrer_info.num_referrals
+                .checked_add(1)
+                .ok_or(ErrorCode::NumericalOverflowError)?;
+            referrer_info.rewards_paid = referrer_info.rewards_paid
+                .checked_add(reward)
+                .ok_or(ErrorCode::NumericalOverflowError)?;
+            referrer_info.bump = referrer_info_bump;
+            referrer_info.exit(ctx.program_id)?;
+
+            let (mut referral_info, referral_info_bump) = load_or_create_program_account::<ReferralInfo>(
+                referral_info_info,
+                &ctx.accounts.payer.to_account_info(),
+                &ctx.accounts.system_program,
+                &ctx.accounts.rent,
+                ReferralInfo::SIZE,
+                &[
+                    PDA_PREFIX,
+                    config.authority.as_ref(),
+                    &tag.uid.to_le_bytes(),
+                    REFERRED,
+                    ctx.accounts.user.key().as_ref(),
+                ],
+            )?;
+            referral_info.tag = tag.key();
+            referral_info.user = ctx.accounts.user.key();
+            referral_info.referrer = referrer.key();
+            referral_info.bump = referral_info_bump;
+            referral_info.exit(ctx.program_id)?;
+
+            tag.referral_spent = tag.referral_spent
+                .checked_add(reward)
+                .ok_or(ErrorCode::NumericalOverflowError)?;
+        }
+    }
+
+    // If the Sprinkle is sponsored, reimburse the payer for the rent of the accounts this claim created,
+    // from the Bakery Treasury.
+    // CandyMachineDrops are not sponsored, as their payer can also be paying the Candy Machine price.
+    let mut sponsored_rent = 0;
+    if let Some(treasury) = &ctx.accounts.treasury {
+        let tag = &mut ctx.accounts.tag;
+        if tag.sponsorship_limit > 0 && tag.tag_type != TagType::CandyMachineDrop {
+            let treasury_info = treasury.to_account_info();
+            let payer_info = ctx.accounts.payer.to_account_info();
+            let rent_spent = created_accounts_rent(&uncreated_accounts, &ctx.accounts.rent);
+            let reimbursement = rent_spent
+                .min(tag.sponsorship_limit.saturating_sub(tag.sponsored_lamports))
+                .min(Treasury::available_lamports(&treasury_info)?);
+
+            **treasury_info.try_borrow_mut_lamports()? -= reimbursement;
+            **payer_info.try_borrow_mut_lamports()? += reimbursement;
+            tag.sponsored_lamports = tag.sponsored_lamports
+                .checked_add(reimbursement)
+                .ok_or(ErrorCode::NumericalOverflowError)?;
+            sponsored_rent = reimbursement;
+        }
+    }
+
+    // A new Raffle entry, VestingEscrow or ClaimEscrow refunds its rent to the Treasury when closed,
+    // if the Treasury covered it.
+    if let Some(mut raffle_entry) = new_raffle_entry {
+        if let Some(treasury) = &ctx.accounts.treasury {
+            if sponsored_rent >= ctx.accounts.rent.minimum_balance(RaffleEntry::SIZE) {
+                raffle_entry.rent_payer = treasury.key();
+            }
+        }
+        raffle_entry.exit(ctx.program_id)?;
+    }
+    if let Some(mut vesting_escrow) = new_vesting_escrow {
+        if let Some(treasury) = &ctx.accounts.treasury {
+            let vesting_rent = ctx.accounts.rent.minimum_balance(VestingEscrow::SIZE)
+                .saturating_add(ctx.accounts.rent.minimum_balance(TokenAccount::LEN));
+            if sponsored_rent >= vesting_rent {
+                vesting_escrow.rent_payer = treasury.key();
+            }
+        }
+        vesting_escrow.exit(ctx.program_id)?;
+    }
+    if let Some(mut escrow) = claim_escrow {
+        if let Some(treasury) = &ctx.accounts.treasury {
+            let escrow_rent = ctx.accounts.rent.minimum_balance(ClaimEscrow::SIZE)
+                .saturating_add(ctx.accounts.rent.minimum_balance(TokenAccount::LEN));
+            if new_claim_escrow && sponsored_rent >= escrow_rent {
+                escrow.rent_payer = treasury.key();
+            }
+        }
+        escrow.exit(ctx.program_id)?;
+    }
+
+    // Run the Sprinkle's claim hook last, once the claim is fully recorded.
+    // Its accounts are passed just before the price accounts, and a failing hook fails the claim.
+    // For claims driven by an authority program, the hook runs one CPI level deeper, and the runtime
+    // refuses it calling back into the authority program, so it can not re-enter the claim.
+    if ctx.accounts.tag.claim_hook.is_some() {
+        let tag = &ctx.accounts.tag;
+        let hook_accounts_end = ctx.remaining_accounts
+            .len()
+            .checked_sub(tag.num_price_accounts() + num_gate_accounts)
+            .ok_or(ErrorCode::MissingClaimHookAccounts)?;
+        let hook_accounts = &ctx.remaining_accounts[hook_accounts_end
+            .checked_sub(tag.num_hook_accounts())
+            .ok_or(ErrorCode::MissingClaimHookAccounts)?..hook_accounts_end];
+        let args = ClaimHookArgs {
+            tag: tag.key(),
+            user: ctx.accounts.user.key(),
+            amount: amount_to_claim,
+            claim_index,
+        };
+
+        // Write the updated counters out first, so the hook reads them if it loads the Sprinkle.
+        ctx.accounts.tag.exit(ctx.program_id)?;
+        run_claim_hook(hook_accounts, &ctx.accounts.tag, &config.authority, args)?;
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Claims through the original claim_tag accounts, with default ClaimTagParams.
+pub fn legacy_handler<'a, 'b, 'c, 'info>(
+  ctx: Context<'a, 'b, 'c, 'info, ClaimTag<'info>>,
+  creator_bump: u8,
+) -> Result<()> {
+    let mut accounts = ClaimTagV2 {
+        user: ctx.accounts.user.clone(),
+        payer: ctx.accounts.payer.clone(),
+        config: ctx.accounts.config.clone(),
+        tag_authority: ctx.accounts.tag_authority.clone(),
+        tag: ctx.accounts.tag.clone(),
+        user_info: ctx.accounts.user_info.clone(),
+        system_program: ctx.accounts.system_program.clone(),
+        token_program: ctx.accounts.token_program.clone(),
+        rent: ctx.accounts.rent.clone(),
+        quest: None,
+        quest_progress: None,
+        campaign: None,
+        campaign_user_info: None,
+        session_key: None,
+        treasury: None,
+        associated_token_program: None,
+        token_mint: None,
+        token_2022_program: None,
+        referrer: None,
+        referrer_info: None,
+        treasury_token: None,
+        referrer_token: None,
+        referral_info: None,
+    };
+    handler(
+        Context::new(ctx.program_id, &mut accounts, ctx.remaining_accounts, ctx.bumps.clone()),
+        creator_bump,
+        ClaimTagParams::default(),
+    )?;
+
+    // Hand the updated state back, so it is what gets persisted when this instruction exits.
+    ctx.accounts.config.set_inner(accounts.config.into_inner());
+    ctx.accounts.tag.set_inner(accounts.tag.into_inner());
+
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, RAFFLE};
+use crate::state::{bakery::*, raffle::*, sprinkle::*};
+
+#[derive(Accounts)]
+pub struct CloseRaffleEntry<'info> {
+    /// The user who entered the Raffle.
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    pub config: Box<Account<'info, Config>>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// PDA which stores the user's entry in the Raffle.
+    #[account(mut,
+              close = rent_payer,
+              has_one = tag,
+              has_one = user,
+              has_one = rent_payer,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  &tag.uid.to_le_bytes(),
+                  RAFFLE,
+                  user.key().as_ref(),
+                  &raffle_entry.season_seed()
+              ],
+              bump = raffle_entry.bump)]
+    pub raffle_entry: Box<Account<'info, RaffleEntry>>,
+
+    /// The account which paid the RaffleEntry's rent, either the entry's payer or the Bakery Treasury.
+    /// CHECK: Verified as the entry's rent_payer, and only receives its rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+}
+
+/// Closes a losing Raffle entry, returning its rent to whoever paid it.
+/// Entries of past seasons, or of a Sprinkle which is no longer a Raffle, can always be closed,
+/// those of the current one once it is drawn.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, CloseRaffleEntry<'info>>,
+) -> Result<()> {
+    let tag = &ctx.accounts.tag;
+    let raffle_entry = &ctx.accounts.raffle_entry;
+
+    require!(
+        raffle_entry.season != tag.season
+            || tag.tag_type != TagType::Raffle
+            || tag.raffle_seed.is_some(),
+        ErrorCode::RaffleNotDrawn
+    );
+    require!(!raffle_entry.is_winner(tag), ErrorCode::RaffleWinnerCanNotClose);
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{keccak, sysvar::slot_hashes};
+use crate::errors::ErrorCode;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, sprinkle::*};
+
+#[derive(Accounts)]
+pub struct DrawRaffle<'info> {
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    pub config: Box<Account<'info, Config>>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// SlotHashes Sysvar, providing the entropy of the draw.
+    /// CHECK: Verified by address, and read as raw bytes since it is too large to deserialize.
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// Account which has the authority to execute claims for this Sprinkle.
+    /// Only required to commit the draw to a new slot, once the committed slot's hash has expired.
+    pub tag_authority: Option<Signer<'info>>,
+}
+
+/// Emitted when a Raffle's draw is committed to a new slot, because the hash of the previous one expired.
+#[event]
+pub struct RaffleDrawRecommitted {
+    /// The Raffle Sprinkle being drawn.
+    pub tag: Pubkey,
+
+    /// The slot the draw was committed to, whose hash has left SlotHashes.
+    pub expired_draw_slot: u64,
+
+    /// The slot the draw is now committed to.
+    pub draw_slot: u64,
+}
+
+/// Draws a Raffle in two steps once its entries have closed. The first call commits the draw to a
+/// slot RAFFLE_DRAW_DELAY_SLOTS ahead, and a call after that slot fixes the seed its winners are
+/// picked with, mixing that slot's hash with the Sprinkle and its number of entries.
+/// If the slot's hash has already left SlotHashes, only a Sprinkle authority can commit the draw to a new slot,
+/// so nobody else can re-roll a draw they don't like by letting it expire. Each re-commit is emitted as an event.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, DrawRaffle<'info>>,
+) -> Result<()> {
+    let tag_key = ctx.accounts.tag.key();
+    let tag = &mut ctx.accounts.tag;
+
+    require!(tag.tag_type == TagType::Raffle, ErrorCode::InvalidRaffle);
+    require!(
+        Clock::get()?.unix_timestamp >= tag.raffle_ends_at,
+        ErrorCode::RaffleStillOpen
+    );
+    require!(tag.raffle_seed.is_none(), ErrorCode::RaffleAlreadyDrawn);
+
+    if tag.raffle_draw_slot != 0 {
+        match find_draw_slot_hash(&ctx.accounts.slot_hashes.try_borrow_data()?, tag.raffle_draw_slot)? {
+            DrawSlotHash::Pending => return Err(ErrorCode::RaffleDrawSlotPending.into()),
+            DrawSlotHash::Found(draw_hash) => {
+                tag.raffle_seed = Some(
+                    keccak::hashv(&[&draw_hash, tag_key.as_ref(), &tag.num_entries.to_le_bytes()])
+                        .to_bytes()
+                );
+                return Ok(());
+            }
+            DrawSlotHash::Expired => {
+                let tag_authority = ctx.accounts.tag_authority
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidTagAuthority)?;
+                require!(tag.is_tag_authority(&tag_authority.key()), ErrorCode::InvalidTagAuthority);
+            }
+        }
+    }
+
+    let expired_draw_slot = tag.raffle_draw_slot;
+    tag.raffle_draw_slot = Clock::get()?
+        .slot
+        .checked_add(Tag::RAFFLE_DRAW_DELAY_SLOTS)
+        .ok_or(ErrorCode::NumericalOverflowError)?;
+
+    if expired_draw_slot != 0 {
+        emit!(RaffleDrawRecommitted {
+            tag: tag_key,
+            expired_draw_slot,
+            draw_slot: tag.raffle_draw_slot,
+        });
+    }
+
+    Ok(())
+}
+
+enum DrawSlotHash {
+    /// No block has been produced at or after the draw slot yet.
+    Pending,
+    /// The hash of the first block produced at or after the draw slot.
+    Found([u8; 32]),
+    /// The draw slot is older than every slot SlotHashes still holds.
+    Expired,
+}
+
+/// Looks up the hash drawing a Raffle. The draw slot itself may have been skipped, so the first
+/// block produced at or after it is used, which is just as unknown when the draw is committed to.
+fn find_draw_slot_hash(slot_hashes_data: &[u8], draw_slot: u64) -> Result<DrawSlotHash> {
+    // SlotHashes starts with an 8 byte length, followed by (slot, hash) pairs, most recent first.
+    let mut len_bytes = [0u8; 8];
+    len_bytes.copy_from_slice(slot_hashes_data.get(..8).ok_or(ErrorCode::InvalidRaffle)?);
+
+    let mut draw_hash = None;
+    let mut oldest_slot = u64::MAX;
+    for index in 0..u64::from_le_bytes(len_bytes) as usize {
+        let entry = slot_hashes_data
+            .get(8 + index * 40..8 + (index + 1) * 40)
+            .ok_or(ErrorCode::InvalidRaffle)?;
+        let mut slot_bytes = [0u8; 8];
+        slot_bytes.copy_from_slice(&entry[..8]);
+
+        if u64::from_le_bytes(slot_bytes) < draw_slot {
+            return Ok(match draw_hash {
+                Some(hash) => DrawSlotHash::Found(hash),
+                None => DrawSlotHash::Pending,
+            });
+        }
+
+        let mut hash = [0u8; 32];
+        hash.copy_from_slice(&entry[8..]);
+        draw_hash = Some(hash);
+        oldest_slot = u64::from_le_bytes(slot_bytes);
+    }
+
+    // Every slot held is at or after the draw slot. Unless the oldest one is the draw slot itself,
+    // the first block after it may be gone.
+    Ok(match draw_hash {
+        Some(hash) if oldest_slot == draw_slot => DrawSlotHash::Found(hash),
+        Some(_) => DrawSlotHash::Expired,
+        None => DrawSlotHash::Pending,
+    })
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, system_instruction};
+use crate::state::{PDA_PREFIX, TREASURY};
+use crate::state::{bakery::*, treasury::*};
+
+#[derive(Accounts)]
+pub struct FundTreasury<'info> {
+    /// Account which sends the lamports, and pays the rent of the Treasury if it does not exist yet.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    pub config: Account<'info, Config>,
+
+    /// PDA which holds the lamports used to sponsor claims of this Bakery.
+    #[account(init_if_needed,
+              payer = payer,
+              space = Treasury::SIZE,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  TREASURY
+              ],
+              bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+}
+
+/// Tops up the Treasury of a Bakery. Anyone can fund it.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, FundTreasury<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+
+    // A fresh Treasury PDA has just been created by the Anchor constraints.
+    if treasury.config == Pubkey::default() {
+        treasury.config = ctx.accounts.config.key();
+        treasury.bump = *ctx.bumps.get("treasury").unwrap();
+    }
+
+    invoke(
+        &system_instruction::transfer(&ctx.accounts.payer.key(), &treasury.key(), amount),
+        &[
+            ctx.accounts.payer.to_account_info(),
+            treasury.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+        ],
+    )?;
+
+    Ok(())
+}
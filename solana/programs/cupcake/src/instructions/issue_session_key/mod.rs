@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, SESSION};
+use crate::state::{bakery::*, session_key::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct IssueSessionKeyParams {
+    /// The uids of the Sprinkles the key can approve claims for.
+    tags: Vec<u64>,
+
+    /// A Campaign whose Sprinkles the key can also approve claims for.
+    campaign: Option<Pubkey>,
+
+    /// Unix timestamp after which the key can no longer approve claims.
+    expires_at: i64,
+
+    /// The number of claims the key can approve.
+    claim_budget: u64,
+}
+
+#[derive(Accounts)]
+pub struct IssueSessionKey<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// Account which pays the network and rent fees, for this transaction only.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// The key being issued, which will sign claims in place of a tag_authority.
+    /// CHECK: Can be any keypair, it only has to sign during claims.
+    pub key: UncheckedAccount<'info>,
+
+    /// PDA which stores the scope, expiry and budget of the key.
+    /// Re-issuing an existing key overwrites all of them.
+    #[account(init_if_needed,
+              payer = payer,
+              space = SessionKey::SIZE,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  SESSION,
+                  key.key().as_ref()
+              ],
+              bump)]
+    pub session_key: Box<Account<'info, SessionKey>>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, IssueSessionKey<'info>>,
+    params: IssueSessionKeyParams,
+) -> Result<()> {
+    let session_key = &mut ctx.accounts.session_key;
+
+    require!(
+        params.tags.len() <= SessionKey::MAX_TAGS,
+        ErrorCode::TooManySessionKeyTags
+    );
+
+    session_key.config = ctx.accounts.config.key();
+    session_key.key = ctx.accounts.key.key();
+    session_key.tags = params.tags;
+    session_key.campaign = params.campaign;
+    session_key.expires_at = params.expires_at;
+    session_key.claim_budget = params.claim_budget;
+    session_key.bump = *ctx.bumps.get("session_key").unwrap();
+
+    Ok(())
+}
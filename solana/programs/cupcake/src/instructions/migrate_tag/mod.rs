@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::errors::ErrorCode;
+use crate::state::sprinkle::*;
+use crate::utils::grow_program_account;
+
+#[derive(Accounts)]
+pub struct MigrateTag<'info> {
+    /// Account which pays the rent for the Sprinkle's added space.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores data about the state of a Sprinkle, possibly in an earlier, smaller layout.
+    /// CHECK: Deserialized as a Sprinkle in the handler, once it has been grown to the current layout.
+    #[account(mut, owner = crate::ID)]
+    pub tag: UncheckedAccount<'info>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+
+    /// SPL Rent Sysvar, required for account allocation.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// The Sprinkle layout from before versioning.
+/// Only deserialized to find where an unversioned Sprinkle's data ends.
+#[derive(AnchorDeserialize)]
+#[allow(dead_code)]
+struct UnversionedTag {
+    uid: u64,
+    tag_type: TagType,
+    tag_authority: Pubkey,
+    config: Pubkey,
+    total_supply: u64,
+    num_claimed: u64,
+    minter_pays: bool,
+    per_user: u64,
+    token_mint: Pubkey,
+    candy_machine: Pubkey,
+    whitelist_mint: Pubkey,
+    whitelist_burn: bool,
+    bump: u8,
+    current_token_location: Pubkey,
+    vault_state: VaultState,
+    vault_authority: Option<Pubkey>,
+}
+
+/// Grows a Sprinkle baked before versioning to the current Tag::SIZE.
+/// Fields added since then read as zero, which is the default (disabled) value of each of them.
+/// Anyone can run this, since it only adds space to the Sprinkle.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, MigrateTag<'info>>,
+) -> Result<()> {
+    let tag_info = ctx.accounts.tag.to_account_info();
+
+    // Every versioned Sprinkle is created at its full size, so only unversioned ones are smaller.
+    require!(tag_info.data_len() < Tag::SIZE, ErrorCode::TagAlreadyMigrated);
+
+    // Zero everything past the unversioned data. A vault_authority which was set and then cleared
+    // leaves its old key behind, which would otherwise be read as the fields added since.
+    {
+        let mut data = tag_info.try_borrow_mut_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == Tag::discriminator(),
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        let mut unversioned_data: &[u8] = &data[8..];
+        UnversionedTag::deserialize(&mut unversioned_data)?;
+        let unversioned_end = data.len() - unversioned_data.len();
+        data[unversioned_end..].fill(0);
+    }
+
+    grow_program_account(
+        &tag_info,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program,
+        &ctx.accounts.rent,
+        Tag::SIZE,
+    )?;
+
+    let mut tag: Account<Tag> = Account::try_from(&tag_info)?;
+    tag.version = Tag::VERSION;
+    tag.exit(ctx.program_id)?;
+
+    Ok(())
+}
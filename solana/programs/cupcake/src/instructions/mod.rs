@@ -1,23 +1,77 @@
 pub mod accept_offer;
+pub mod airdrop_from_tag;
 pub mod bake_sprinkle;
+pub mod bake_tag_batch;
 pub mod cancel_offer;
 pub mod claim_bought_nft;
+pub mod claim_raffle_prize;
 pub mod claim_sprinkle;
+pub mod close_raffle_entry;
 pub mod create_bakery;
 pub mod delete_listing;
+pub mod draw_raffle;
+pub mod fund_treasury;
+pub mod issue_session_key;
 pub mod make_offer;
+pub mod migrate_tag;
 pub mod modify_listing;
+pub mod reclaim_claim_escrow;
+pub mod redeem_claim_escrow;
+pub mod refill_bundle_slot;
+pub mod revoke_session_key;
+pub mod set_allowlist_root;
+pub mod set_authority_program;
+pub mod set_claim_hook;
+pub mod set_claim_price;
+pub mod set_combo_keys;
+pub mod set_referral_reward;
+pub mod set_tag_template;
+pub mod sync_tag_template;
 pub mod toggle_vault_nft;
+pub mod update_tag_authority;
+pub mod upsert_campaign;
+pub mod upsert_quest;
+pub mod verify_check_in;
+pub mod withdraw_treasury;
+pub mod withdraw_treasury_token;
+pub mod withdraw_vested;
 
 pub use accept_offer::*;
+pub use airdrop_from_tag::*;
 pub use bake_sprinkle::*;
+pub use bake_tag_batch::*;
 pub use cancel_offer::CancelOffer;
 pub use cancel_offer::*;
 pub use claim_bought_nft::*;
+pub use claim_raffle_prize::*;
 pub use claim_sprinkle::*;
+pub use close_raffle_entry::*;
 pub use create_bakery::*;
 pub use delete_listing::*;
+pub use draw_raffle::*;
+pub use fund_treasury::*;
+pub use issue_session_key::*;
 pub use make_offer::*;
+pub use migrate_tag::*;
 pub use modify_listing::*;
+pub use reclaim_claim_escrow::*;
+pub use redeem_claim_escrow::*;
+pub use refill_bundle_slot::*;
+pub use revoke_session_key::*;
+pub use set_allowlist_root::*;
+pub use set_authority_program::*;
+pub use set_claim_hook::*;
+pub use set_claim_price::*;
+pub use set_combo_keys::*;
+pub use set_referral_reward::*;
+pub use set_tag_template::*;
+pub use sync_tag_template::*;
 pub use toggle_vault_nft::ToggleVaultNFT;
 pub use toggle_vault_nft::*;
+pub use update_tag_authority::*;
+pub use upsert_campaign::*;
+pub use upsert_quest::*;
+pub use verify_check_in::*;
+pub use withdraw_treasury::*;
+pub use withdraw_treasury_token::*;
+pub use withdraw_vested::*;
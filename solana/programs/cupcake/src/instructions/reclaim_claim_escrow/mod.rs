@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{self, Token, TokenAccount};
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, ESCROW};
+use crate::state::{bakery::*, claim_escrow::*, sprinkle::*};
+
+#[derive(Accounts)]
+pub struct ReclaimClaimEscrow<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Box<Account<'info, Config>>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// PDA which holds the tokens claimed to an identity, once it has expired.
+    #[account(mut,
+              close = rent_payer,
+              has_one = tag,
+              has_one = rent_payer,
+              constraint = Clock::get()?.unix_timestamp >= claim_escrow.expires_at @ ErrorCode::ClaimEscrowNotExpired,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes(),
+                  ESCROW,
+                  &claim_escrow.identity_hash
+              ],
+              bump = claim_escrow.bump)]
+    pub claim_escrow: Box<Account<'info, ClaimEscrow>>,
+
+    /// ATA of the ClaimEscrow holding its tokens. Closed once emptied.
+    #[account(mut,
+              address = get_associated_token_address(&claim_escrow.key(), &claim_escrow.token_mint))]
+    pub escrow_token: Box<Account<'info, TokenAccount>>,
+
+    /// Token account of the Bakery authority receiving the tokens back.
+    #[account(mut,
+              token::mint = claim_escrow.token_mint,
+              token::authority = authority)]
+    pub authority_token: Box<Account<'info, TokenAccount>>,
+
+    /// Account which paid the rent of the ClaimEscrow and its token account, and receives it back.
+    /// CHECK: Checked against the ClaimEscrow's rent_payer.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+}
+
+/// Returns the tokens of an expired, unredeemed ClaimEscrow to the Bakery, closing the escrow.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, ReclaimClaimEscrow<'info>>,
+) -> Result<()> {
+    let claim_escrow = &ctx.accounts.claim_escrow;
+    let uid = ctx.accounts.tag.uid.to_le_bytes();
+    let escrow_seeds = &[
+        PDA_PREFIX,
+        ctx.accounts.config.authority.as_ref(),
+        &uid,
+        ESCROW,
+        &claim_escrow.identity_hash,
+        &[claim_escrow.bump],
+    ];
+
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.escrow_token.to_account_info(),
+        to: ctx.accounts.authority_token.to_account_info(),
+        authority: claim_escrow.to_account_info(),
+    };
+    let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(context.with_signer(&[&escrow_seeds[..]]), ctx.accounts.escrow_token.amount)?;
+
+    let cpi_accounts = token::CloseAccount {
+        account: ctx.accounts.escrow_token.to_account_info(),
+        destination: ctx.accounts.rent_payer.to_account_info(),
+        authority: claim_escrow.to_account_info(),
+    };
+    let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::close_account(context.with_signer(&[&escrow_seeds[..]]))?;
+
+    Ok(())
+}
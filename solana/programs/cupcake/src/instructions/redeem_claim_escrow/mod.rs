@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{self, Token, TokenAccount};
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, ESCROW};
+use crate::state::{bakery::*, claim_escrow::*, sprinkle::*};
+
+#[derive(Accounts)]
+pub struct RedeemClaimEscrow<'info> {
+    /// Wallet which receives the tokens held by the ClaimEscrow.
+    /// CHECK: User can be any account, the redemption is approved by the tag authority.
+    pub user: UncheckedAccount<'info>,
+
+    /// Account which pays the network fees.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    pub config: Box<Account<'info, Config>>,
+
+    /// Account which has the authority to execute claims for this Sprinkle.
+    /// Its signature approves redeeming the identity's tokens into the user wallet.
+    pub tag_authority: Signer<'info>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(has_one = config,
+              constraint = tag.is_tag_authority(&tag_authority.key()) @ ErrorCode::InvalidTagAuthority,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// PDA which holds the tokens claimed to an identity.
+    #[account(mut,
+              close = rent_payer,
+              has_one = tag,
+              has_one = rent_payer,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  &tag.uid.to_le_bytes(),
+                  ESCROW,
+                  &claim_escrow.identity_hash
+              ],
+              bump = claim_escrow.bump)]
+    pub claim_escrow: Box<Account<'info, ClaimEscrow>>,
+
+    /// ATA of the ClaimEscrow holding its tokens. Closed once emptied.
+    #[account(mut,
+              address = get_associated_token_address(&claim_escrow.key(), &claim_escrow.token_mint))]
+    pub escrow_token: Box<Account<'info, TokenAccount>>,
+
+    /// Token account of the user receiving the tokens.
+    #[account(mut,
+              token::mint = claim_escrow.token_mint,
+              token::authority = user)]
+    pub user_token: Box<Account<'info, TokenAccount>>,
+
+    /// Account which paid the rent of the ClaimEscrow and its token account, and receives it back.
+    /// CHECK: Checked against the ClaimEscrow's rent_payer.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+}
+
+/// Moves every token held by the ClaimEscrow of an identity into a real wallet, closing the escrow.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RedeemClaimEscrow<'info>>,
+) -> Result<()> {
+    let claim_escrow = &ctx.accounts.claim_escrow;
+    let uid = ctx.accounts.tag.uid.to_le_bytes();
+    let escrow_seeds = &[
+        PDA_PREFIX,
+        ctx.accounts.config.authority.as_ref(),
+        &uid,
+        ESCROW,
+        &claim_escrow.identity_hash,
+        &[claim_escrow.bump],
+    ];
+
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.escrow_token.to_account_info(),
+        to: ctx.accounts.user_token.to_account_info(),
+        authority: claim_escrow.to_account_info(),
+    };
+    let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(context.with_signer(&[&escrow_seeds[..]]), ctx.accounts.escrow_token.amount)?;
+
+    let cpi_accounts = token::CloseAccount {
+        account: ctx.accounts.escrow_token.to_account_info(),
+        destination: ctx.accounts.rent_payer.to_account_info(),
+        authority: claim_escrow.to_account_info(),
+    };
+    let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::close_account(context.with_signer(&[&escrow_seeds[..]]))?;
+
+    Ok(())
+}
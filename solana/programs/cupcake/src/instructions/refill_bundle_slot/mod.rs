@@ -0,0 +1,185 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::*;
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, BUNDLE};
+use crate::state::{bakery::*, bundle::*, sprinkle::*};
+use crate::utils::assert_is_ata;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq)]
+pub struct RefillBundleSlotParams {
+    /// The index of the slot to refill. Passing the current number of slots adds a new one.
+    slot: u8,
+
+    /// The kind of asset held in this slot.
+    kind: BundleSlotKind,
+
+    /// The number of tokens transferred from this slot on each claim.
+    amount: u64,
+
+    /// The number of additional claims this slot should be funded for.
+    /// Ignored for NonFungible slots, which are always refilled one claim at a time.
+    num_claims: u64,
+}
+
+#[derive(Accounts)]
+pub struct RefillBundleSlot<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Account which pays the network and rent fees, for this transaction only.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              constraint = tag.tag_type == TagType::Bundle @ ErrorCode::NotABundle,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// PDA which stores the asset slots of a Bundle Sprinkle.
+    #[account(init_if_needed,
+              payer = payer,
+              space = Bundle::SIZE,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes(),
+                  BUNDLE
+              ],
+              bump)]
+    pub bundle: Box<Account<'info, Bundle>>,
+
+    /// The mint of the asset held in the slot.
+    pub token_mint: Account<'info, Mint>,
+
+    /// ATA of token_mint type owned by the Bakery authority, delegated to the Bakery PDA.
+    /// CHECK: Verified as an ATA of the Bakery authority in the handler.
+    #[account(mut)]
+    pub token: UncheckedAccount<'info>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+
+    /// SPL Rent Sysvar, required for account allocation.
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, RefillBundleSlot<'info>>,
+    params: RefillBundleSlotParams
+) -> Result<()> {
+    let tag = &mut ctx.accounts.tag;
+    let bundle = &mut ctx.accounts.bundle;
+    let token_mint = &ctx.accounts.token_mint;
+    let slot_index = params.slot as usize;
+
+    // A fresh Bundle PDA has just been created by the Anchor constraints.
+    if bundle.tag == Pubkey::default() {
+        bundle.tag = tag.key();
+        bundle.bump = *ctx.bumps.get("bundle").unwrap();
+    }
+
+    // NonFungible slots always hand out exactly one token per claim.
+    require!(
+        params.kind == BundleSlotKind::Fungible
+            || (params.amount == 1 && token_mint.decimals == 0),
+        ErrorCode::InvalidBundleSlot
+    );
+    require!(params.amount > 0, ErrorCode::InvalidBundleSlot);
+
+    // Slots of the same mint would share the authority's ATA, and each approval would overwrite
+    // the delegation made for the other.
+    require!(
+        bundle.slots
+            .iter()
+            .enumerate()
+            .all(|(index, slot)| index == slot_index || slot.token_mint != token_mint.key()),
+        ErrorCode::DuplicateBundleMint
+    );
+
+    // Either append a new slot, or grab the existing one being refilled.
+    if slot_index == bundle.slots.len() {
+        require!(
+            bundle.slots.len() < Bundle::MAX_SLOTS,
+            ErrorCode::TooManyBundleSlots
+        );
+        bundle.slots.push(BundleSlot {
+            kind: params.kind,
+            token_mint: token_mint.key(),
+            amount: params.amount,
+            total_supply: tag.num_claimed,
+        });
+    }
+    require!(slot_index < bundle.slots.len(), ErrorCode::InvalidBundleSlot);
+    let slot = &mut bundle.slots[slot_index];
+    let is_empty = slot.total_supply <= tag.num_claimed;
+
+    // The asset in a slot can only be swapped out once the slot has been emptied.
+    require!(
+        is_empty || slot.token_mint == token_mint.key(),
+        ErrorCode::PublicKeyMismatch
+    );
+
+    slot.total_supply = match params.kind {
+        // Like a Refillable1Of1, only add a claim if the previous NFT is gone.
+        BundleSlotKind::NonFungible => {
+            require!(is_empty, ErrorCode::BundleSlotNotEmpty);
+            tag.num_claimed
+                .checked_add(1)
+                .ok_or(ErrorCode::NumericalOverflowError)?
+        }
+
+        BundleSlotKind::Fungible => std::cmp::max(slot.total_supply, tag.num_claimed)
+            .checked_add(params.num_claims)
+            .ok_or(ErrorCode::NumericalOverflowError)?,
+    };
+    slot.kind = params.kind;
+    slot.token_mint = token_mint.key();
+    slot.amount = params.amount;
+
+    // Check that the provided ATA is legitimate.
+    assert_is_ata(
+        &ctx.accounts.token,
+        &ctx.accounts.config.authority,
+        &token_mint.key(),
+        Some(&ctx.accounts.config.key()),
+    )?;
+
+    // Delegate enough tokens to the Bakery PDA to cover every remaining claim of this slot.
+    let delegated_amount = slot.total_supply
+        .checked_sub(tag.num_claimed)
+        .ok_or(ErrorCode::NumericalOverflowError)?
+        .checked_mul(slot.amount)
+        .ok_or(ErrorCode::NumericalOverflowError)?;
+    let cpi_accounts = Approve {
+        to: ctx.accounts.token.to_account_info(),
+        delegate: ctx.accounts.config.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+    };
+    let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    approve(context, delegated_amount)?;
+
+    // The Sprinkle can only serve as many claims as its least-funded slot.
+    tag.total_supply = bundle.total_supply();
+
+    Ok(())
+}
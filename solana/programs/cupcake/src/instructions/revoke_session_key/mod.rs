@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::{PDA_PREFIX, SESSION};
+use crate::state::{bakery::*, session_key::*};
+
+#[derive(Accounts)]
+pub struct RevokeSessionKey<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    /// Receives the rent of the closed SessionKey.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA of the key being revoked.
+    #[account(mut,
+              close = authority,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  SESSION,
+                  session_key.key.as_ref()
+              ],
+              bump = session_key.bump)]
+    pub session_key: Box<Account<'info, SessionKey>>,
+}
+
+pub fn handler<'a, 'b, 'c, 'info>(
+    _ctx: Context<'a, 'b, 'c, 'info, RevokeSessionKey<'info>>,
+) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, sprinkle::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq)]
+pub struct SetAllowlistRootParams {
+    /// Root of the Merkle tree of users allowed to claim. Set to None to open the Sprinkle to everyone.
+    allowlist_root: Option<[u8; 32]>,
+}
+
+#[derive(Accounts)]
+pub struct SetAllowlistRoot<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+}
+
+/// Replaces the allowlist of a Sprinkle. Claim counters are kept, so users who already
+/// claimed keep counting against their allocation in the new tree.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SetAllowlistRoot<'info>>,
+    params: SetAllowlistRootParams,
+) -> Result<()> {
+    ctx.accounts.tag.allowlist_root = params.allowlist_root;
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::{CLAIM_AUTHORITY, PDA_PREFIX};
+use crate::state::{bakery::*, sprinkle::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq)]
+pub struct SetAuthorityProgramParams {
+    /// The program which can sign claims over CPI. Set to None to only accept the Sprinkle's other authorities.
+    authority_program: Option<Pubkey>,
+}
+
+#[derive(Accounts)]
+pub struct SetAuthorityProgram<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+}
+
+/// Registers the program allowed to drive claims on a Sprinkle over CPI.
+/// Its claim authority PDA is derived once here, so claims only compare keys.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SetAuthorityProgram<'info>>,
+    params: SetAuthorityProgramParams,
+) -> Result<()> {
+    let tag_key = ctx.accounts.tag.key();
+    let tag = &mut ctx.accounts.tag;
+
+    tag.program_authority = match params.authority_program {
+        Some(authority_program) => {
+            // The Sprinkle's claim hook runs inside each claim, so it can not also be the program driving them.
+            require!(
+                authority_program != crate::ID && tag.claim_hook != Some(authority_program),
+                ErrorCode::InvalidAuthorityProgram
+            );
+            let (program_authority, _) = Pubkey::find_program_address(
+                &[CLAIM_AUTHORITY, tag_key.as_ref()],
+                &authority_program,
+            );
+            Some(program_authority)
+        }
+        None => None,
+    };
+    tag.authority_program = params.authority_program;
+
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, sprinkle::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq)]
+pub struct SetClaimHookParams {
+    /// The program to invoke through the on_claim interface after each claim. Set to None to remove the hook.
+    claim_hook: Option<Pubkey>,
+
+    /// The number of accounts the hook takes, on top of the hook program and hook authority.
+    num_claim_hook_accounts: u8,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimHook<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+}
+
+/// Sets or removes the program a Sprinkle invokes after each claim.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SetClaimHook<'info>>,
+    params: SetClaimHookParams,
+) -> Result<()> {
+    let tag = &mut ctx.accounts.tag;
+
+    // Hooks can not call back into this program, be the program driving claims over CPI,
+    // nor take an unbounded number of accounts.
+    if let Some(claim_hook) = params.claim_hook {
+        require!(
+            claim_hook != crate::ID
+                && tag.authority_program != Some(claim_hook)
+                && params.num_claim_hook_accounts <= Tag::MAX_CLAIM_HOOK_ACCOUNTS,
+            ErrorCode::InvalidClaimHook
+        );
+        tag.num_claim_hook_accounts = params.num_claim_hook_accounts;
+    } else {
+        tag.num_claim_hook_accounts = 0;
+    }
+    tag.claim_hook = params.claim_hook;
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, sprinkle::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct SetClaimPriceParams {
+    /// The price of each claim, in claim_price_mint tokens (or lamports if unset). Set to 0 for free claims.
+    claim_price: u64,
+
+    /// The mint address of the SPL token claims are paid in. If unset, claims are paid in SOL.
+    claim_price_mint: Option<Pubkey>,
+
+    /// If this is true, the user pays the claim price instead of the transaction payer.
+    user_pays_price: bool,
+
+    /// The recipients the claim price is split between. Their shares must add up to 10000 basis points.
+    revenue_splits: Vec<RevenueSplit>,
+
+    /// How the claim price rises with each claim, starting from claim_price.
+    price_curve: PriceCurve,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimPrice<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+}
+
+/// Sets the price of each claim of a Sprinkle, how it rises, and how its revenue is split.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SetClaimPrice<'info>>,
+    params: SetClaimPriceParams,
+) -> Result<()> {
+    let tag = &mut ctx.accounts.tag;
+
+    // Free claims need no recipients, paid ones need every basis point accounted for.
+    Tag::validate_revenue_shares(params.claim_price, &params.revenue_splits)?;
+    if params.claim_price > 0 {
+        tag.revenue_splits = params.revenue_splits;
+    } else {
+        tag.revenue_splits = vec![];
+    }
+
+    tag.claim_price = params.claim_price;
+    tag.claim_price_mint = params.claim_price_mint;
+    tag.user_pays_price = params.user_pays_price;
+    tag.price_curve = params.price_curve;
+
+    Ok(())
+}
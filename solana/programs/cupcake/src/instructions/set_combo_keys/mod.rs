@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, sprinkle::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct SetComboKeysParams {
+    /// The chip keys (or other Sprinkles' authorities) which must all attest to each claim.
+    /// Set to an empty list to make the Sprinkle claimable with its tag_authority alone.
+    combo_keys: Vec<Pubkey>,
+}
+
+#[derive(Accounts)]
+pub struct SetComboKeys<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+}
+
+/// Sets the keys which must all attest to each claim of a Sprinkle, turning it into a combo.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SetComboKeys<'info>>,
+    params: SetComboKeysParams,
+) -> Result<()> {
+    let tag = &mut ctx.accounts.tag;
+
+    // Every key has to be distinct, or a single chip could stand in for several.
+    let mut sorted_keys = params.combo_keys.clone();
+    sorted_keys.sort();
+    sorted_keys.dedup();
+    require!(
+        params.combo_keys.len() <= Tag::MAX_COMBO_KEYS
+            && sorted_keys.len() == params.combo_keys.len(),
+        ErrorCode::InvalidComboKeys
+    );
+    tag.combo_keys = params.combo_keys;
+
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, sprinkle::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq)]
+pub struct SetReferralRewardParams {
+    /// The reward paid to the referrer of each user's first claim, in referral_reward_mint tokens
+    /// (or lamports if unset). Set to 0 to disable referral rewards.
+    referral_reward: u64,
+
+    /// The mint address of the SPL token referral rewards are paid in. If unset, rewards are paid in SOL.
+    referral_reward_mint: Option<Pubkey>,
+
+    /// The total referral rewards the Sprinkle can pay out, including those already paid.
+    referral_budget: u64,
+
+    /// The number of referrals a single referrer can be rewarded for. Set to 0 for no limit.
+    per_referrer_cap: u64,
+}
+
+#[derive(Accounts)]
+pub struct SetReferralReward<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+}
+
+/// Sets the referral reward of a Sprinkle and the budget it is paid from.
+/// The rewards already paid keep counting against the new budget.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SetReferralReward<'info>>,
+    params: SetReferralRewardParams,
+) -> Result<()> {
+    let tag = &mut ctx.accounts.tag;
+
+    tag.referral_reward = params.referral_reward;
+    tag.referral_reward_mint = params.referral_reward_mint;
+    tag.referral_budget = params.referral_budget;
+    tag.per_referrer_cap = params.per_referrer_cap;
+
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, TEMPLATE};
+use crate::state::{bakery::*, sprinkle::*, tag_template::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct SetTagTemplateParams {
+    /// The unique identifier for this TagTemplate, used in PDA generation.
+    template_id: u64,
+
+    /// The claim method Sprinkles baked from this template use.
+    tag_type: TagType,
+
+    /// The total amount of claims that can be executed from each Sprinkle.
+    num_claims: u64,
+
+    /// The total number of claims an individual user can execute from each Sprinkle.
+    per_user: u64,
+
+    /// Length in seconds of the period over which per_period is enforced. Set to 0 to disable period limits.
+    claim_period: i64,
+
+    /// The number of claims an individual user can execute from each Sprinkle within one claim_period.
+    per_period: u64,
+
+    /// The holding requirement claimers must meet, if any.
+    gate: Option<TagGate>,
+
+    /// The total lamports the Bakery Treasury can spend reimbursing the rent costs of claims, per Sprinkle.
+    sponsorship_limit: u64,
+
+    /// Number of seconds tokens claimed to an identity stay redeemable. Set to 0 to disable claims to an identity.
+    escrow_duration: i64,
+
+    /// The price of each claim, in claim_price_mint tokens (or lamports if unset). Set to 0 for free claims.
+    claim_price: u64,
+
+    /// The mint address of the SPL token claims are paid in. If unset, claims are paid in SOL.
+    claim_price_mint: Option<Pubkey>,
+
+    /// If this is true, the user pays the claim price instead of the transaction payer.
+    user_pays_price: bool,
+
+    /// The recipients the claim price is split between. Their shares must add up to 10000 basis points.
+    revenue_splits: Vec<RevenueSplit>,
+
+    /// How the claim price rises with each claim, starting from claim_price.
+    price_curve: PriceCurve,
+}
+
+#[derive(Accounts)]
+#[instruction(params: SetTagTemplateParams)]
+pub struct SetTagTemplate<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// Account which pays the network and rent fees, for this transaction only.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores the defaults shared by the Sprinkles baked from it.
+    #[account(init_if_needed,
+              payer = payer,
+              space = TagTemplate::SIZE,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  TEMPLATE,
+                  &params.template_id.to_le_bytes()
+              ],
+              bump)]
+    pub template: Box<Account<'info, TagTemplate>>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates a TagTemplate, or updates an existing one. Sprinkles already baked from it
+/// only pick the changes up once sync_tag_template is run on them.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SetTagTemplate<'info>>,
+    params: SetTagTemplateParams,
+) -> Result<()> {
+    let template = &mut ctx.accounts.template;
+
+    // Validate the defaults the same way add_or_refill_tag and set_claim_price do.
+    Tag::validate_claim_period(params.claim_period, params.per_period)?;
+    require!(params.escrow_duration >= 0, ErrorCode::InvalidEscrowDuration);
+    if let Some(gate) = params.gate {
+        gate.validate()?;
+    }
+    Tag::validate_revenue_shares(params.claim_price, &params.revenue_splits)?;
+    if params.claim_price > 0 {
+        template.revenue_splits = params.revenue_splits;
+    } else {
+        template.revenue_splits = vec![];
+    }
+
+    template.config = ctx.accounts.config.key();
+    template.template_id = params.template_id;
+    template.tag_type = params.tag_type;
+    template.num_claims = params.num_claims;
+    template.per_user = params.per_user;
+    template.claim_period = params.claim_period;
+    template.per_period = params.per_period;
+    template.gate = params.gate;
+    template.sponsorship_limit = params.sponsorship_limit;
+    template.escrow_duration = params.escrow_duration;
+    template.claim_price = params.claim_price;
+    template.claim_price_mint = params.claim_price_mint;
+    template.user_pays_price = params.user_pays_price;
+    template.price_curve = params.price_curve;
+    template.bump = *ctx.bumps.get("template").unwrap();
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, TEMPLATE};
+use crate::state::{bakery::*, sprinkle::*, tag_template::*};
+
+#[derive(Accounts)]
+pub struct SyncTagTemplate<'info> {
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores the defaults shared by the Sprinkles baked from it.
+    #[account(has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  TEMPLATE,
+                  &template.template_id.to_le_bytes()
+              ],
+              bump = template.bump)]
+    pub template: Box<Account<'info, TagTemplate>>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              constraint = tag.template == Some(template.key()) @ ErrorCode::TagNotFromTemplate,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+}
+
+/// Copies the current defaults of a TagTemplate onto a Sprinkle baked from it.
+/// Anyone can run this, since it only applies changes the Bakery authority already made.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, SyncTagTemplate<'info>>,
+) -> Result<()> {
+    ctx.accounts.template.apply_to(&mut ctx.accounts.tag);
+
+    Ok(())
+}
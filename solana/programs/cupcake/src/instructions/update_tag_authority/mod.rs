@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, sprinkle::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct UpdateTagAuthorityParams {
+    /// The address of the account which must sign to approve claims on this Sprinkle.
+    tag_authority: Pubkey,
+
+    /// Additional accounts which can sign to approve claims, any one of them is enough.
+    additional_authorities: Vec<Pubkey>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateTagAuthority<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+}
+
+/// Rotates the claim authorities of a Sprinkle, without touching its assets or delegations.
+/// Unlike re-baking, this is allowed for every type of Sprinkle, including SingleUse1Of1s.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, UpdateTagAuthority<'info>>,
+    params: UpdateTagAuthorityParams,
+) -> Result<()> {
+    let tag = &mut ctx.accounts.tag;
+
+    require!(
+        params.additional_authorities.len() <= Tag::MAX_ADDITIONAL_AUTHORITIES,
+        ErrorCode::TooManyTagAuthorities
+    );
+
+    tag.tag_authority = params.tag_authority;
+    tag.additional_authorities = params.additional_authorities;
+
+    Ok(())
+}
@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use crate::state::{PDA_PREFIX, CAMPAIGN};
+use crate::state::{bakery::*, campaign::*, sprinkle::*};
+use crate::utils::assert_keys_equal;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq)]
+pub struct UpsertCampaignParams {
+    /// The unique identifier for this Campaign, used in PDA generation.
+    id: u64,
+
+    /// The total amount of claims that can be executed across all Sprinkles of this Campaign.
+    /// Set to 0 for no limit.
+    total_supply: u64,
+
+    /// The total number of claims an individual user can execute across all Sprinkles of this Campaign.
+    /// Set to 0 for no limit.
+    per_user: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: UpsertCampaignParams)]
+pub struct UpsertCampaign<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Account which pays the network and rent fees, for this transaction only.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores the shared supply and counters of a Campaign.
+    #[account(init_if_needed,
+              payer = payer,
+              space = Campaign::SIZE,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  CAMPAIGN,
+                  &params.id.to_le_bytes()
+              ],
+              bump)]
+    pub campaign: Box<Account<'info, Campaign>>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+}
+
+// Remaining accounts - 
+    // tag (w) - PDA of each Sprinkle to add to this Campaign, any number of them.
+// -
+
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, UpsertCampaign<'info>>,
+    params: UpsertCampaignParams,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let campaign = &mut ctx.accounts.campaign;
+
+    // Counters are left unchanged when an existing Campaign is updated.
+    campaign.config = config.key();
+    campaign.id = params.id;
+    campaign.total_supply = params.total_supply;
+    campaign.per_user = params.per_user;
+    campaign.bump = *ctx.bumps.get("campaign").unwrap();
+
+    // Link the provided Sprinkles, so their claims draw from the Campaign.
+    for tag_info in ctx.remaining_accounts.iter() {
+        let mut tag: Account<Tag> = Account::try_from(tag_info)?;
+        assert_keys_equal(tag.config, config.key())?;
+        tag.campaign = Some(campaign.key());
+        tag.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
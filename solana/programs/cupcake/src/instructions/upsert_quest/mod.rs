@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, QUEST};
+use crate::state::{bakery::*, quest::*, sprinkle::*};
+use crate::utils::assert_keys_equal;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub struct UpsertQuestParams {
+    /// The unique identifier for this Quest, used in PDA generation.
+    id: u64,
+
+    /// The uids of the Sprinkles which count towards this Quest.
+    /// Existing members must be kept in the same order, new ones can only be appended.
+    members: Vec<u64>,
+
+    /// The uid of the Sprinkle which can only be claimed once this Quest is completed.
+    reward_tag: u64,
+
+    /// The number of distinct members a user needs to claim. Set to 0 to require all of them.
+    required: u8,
+}
+
+#[derive(Accounts)]
+#[instruction(params: UpsertQuestParams)]
+pub struct UpsertQuest<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Account which pays the network and rent fees, for this transaction only.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which stores the members and reward of a Quest.
+    #[account(init_if_needed,
+              payer = payer,
+              space = Quest::SIZE,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  QUEST,
+                  &params.id.to_le_bytes()
+              ],
+              bump)]
+    pub quest: Box<Account<'info, Quest>>,
+
+    /// PDA of the Sprinkle which can only be claimed once the Quest is completed.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  &params.reward_tag.to_le_bytes()
+              ],
+              bump = reward_tag.bump)]
+    pub reward_tag: Box<Account<'info, Tag>>,
+
+    /// SPL System Program, required for account allocation.
+    pub system_program: Program<'info, System>,
+
+    /// PDA of the Quest's current reward Sprinkle, which is unlinked from the Quest.
+    /// Required if the reward Sprinkle is being changed.
+    #[account(mut, has_one = config)]
+    pub previous_reward_tag: Option<Box<Account<'info, Tag>>>,
+}
+
+// Remaining accounts - 
+    // member_tag (w) - PDA of each member Sprinkle to link to this Quest, any number of them.
+// -
+
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, UpsertQuest<'info>>,
+    params: UpsertQuestParams,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let quest = &mut ctx.accounts.quest;
+    let reward_tag = &mut ctx.accounts.reward_tag;
+
+    // User progress is stored as a bitmask over member positions,
+    // so members can not be removed or moved around once set.
+    require!(
+        params.members.len() <= Quest::MAX_MEMBERS,
+        ErrorCode::TooManyQuestMembers
+    );
+    require!(
+        params.members.starts_with(&quest.members),
+        ErrorCode::QuestMembersImmutable
+    );
+    require!(
+        params.required as usize <= params.members.len(),
+        ErrorCode::InvalidQuestRequirement
+    );
+
+    // Unlink the previous reward Sprinkle, so it is no longer gated behind the Quest.
+    let is_existing_quest = quest.config != Pubkey::default();
+    if is_existing_quest && quest.reward_tag != params.reward_tag {
+        let previous_reward_tag = ctx.accounts.previous_reward_tag
+            .as_mut()
+            .ok_or(ErrorCode::MissingPreviousRewardTag)?;
+        require!(
+            previous_reward_tag.uid == quest.reward_tag,
+            ErrorCode::MissingPreviousRewardTag
+        );
+        if previous_reward_tag.quest == Some(quest.key()) {
+            previous_reward_tag.quest = None;
+        }
+    }
+
+    quest.config = config.key();
+    quest.id = params.id;
+    quest.members = params.members;
+    quest.reward_tag = params.reward_tag;
+    quest.required = params.required;
+    quest.bump = *ctx.bumps.get("quest").unwrap();
+
+    // Link the reward Sprinkle, so it can not be claimed without completing the Quest.
+    // A Sprinkle belongs to at most one Quest, so Sprinkles already linked to another one are rejected.
+    require!(
+        reward_tag.quest.is_none() || reward_tag.quest == Some(quest.key()),
+        ErrorCode::TagInAnotherQuest
+    );
+    reward_tag.quest = Some(quest.key());
+
+    // Link the provided member Sprinkles, so their claims count towards the Quest.
+    for member_info in ctx.remaining_accounts.iter() {
+        let mut member: Account<Tag> = Account::try_from(member_info)?;
+        assert_keys_equal(member.config, config.key())?;
+        require!(
+            quest.member_index(member.uid).is_some(),
+            ErrorCode::NotAQuestMember
+        );
+        require!(
+            member.quest.is_none() || member.quest == Some(quest.key()),
+            ErrorCode::TagInAnotherQuest
+        );
+        member.quest = Some(quest.key());
+        member.exit(ctx.program_id)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::PDA_PREFIX;
+use crate::state::{bakery::*, sprinkle::*, user_info::*};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq)]
+pub struct VerifyCheckInParams {
+    /// The minimum number of times the user must have claimed the Sprinkle.
+    min_claims: u64,
+
+    /// The unix timestamp the user's latest claim must be at or after.
+    since: i64,
+}
+
+#[derive(Accounts)]
+pub struct VerifyCheckIn<'info> {
+    /// Account whose presence is being verified.
+    /// CHECK: User can be any account that has claimed the Sprinkle.
+    pub user: UncheckedAccount<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    pub config: Box<Account<'info, Config>>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// PDA which stores a counter of how many times this user has claimed this Sprinkle, in its current season.
+    #[account(seeds = [
+                  PDA_PREFIX,
+                  config.authority.as_ref(),
+                  &tag.uid.to_le_bytes(),
+                  user.key().as_ref(),
+                  &tag.season_seed()
+              ],
+              bump)]
+    pub user_info: Box<Account<'info, UserInfo>>,
+}
+
+/// Fails unless the user has claimed the Sprinkle often and recently enough.
+/// Meant to be called over CPI by other programs as a proof of presence for CheckIn Sprinkles.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, VerifyCheckIn<'info>>,
+    params: VerifyCheckInParams,
+) -> Result<()> {
+    let user_info = &ctx.accounts.user_info;
+
+    require!(
+        user_info.num_claimed > 0
+            && user_info.num_claimed >= params.min_claims
+            && user_info.last_claimed_at >= params.since,
+        ErrorCode::NotCheckedIn
+    );
+
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, TREASURY};
+use crate::state::{bakery::*, treasury::*};
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    /// Receives the withdrawn lamports.
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which holds the lamports used to sponsor claims of this Bakery.
+    #[account(mut,
+              has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  TREASURY
+              ],
+              bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+}
+
+/// Withdraws lamports from the Treasury of a Bakery, leaving it rent exempt.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, WithdrawTreasury<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+    require!(
+        amount <= Treasury::available_lamports(&treasury_info)?,
+        ErrorCode::InsufficientTreasuryFunds
+    );
+
+    **treasury_info.try_borrow_mut_lamports()? -= amount;
+    **ctx.accounts.authority.to_account_info().try_borrow_mut_lamports()? += amount;
+
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, TREASURY};
+use crate::state::{bakery::*, treasury::*};
+use crate::utils::assert_is_ata;
+
+#[derive(Accounts)]
+pub struct WithdrawTreasuryToken<'info> {
+    /// Account which has the authority to create/update sprinkles for this Bakery.
+    pub authority: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    #[account(has_one = authority,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref()
+              ],
+              bump = config.bump)]
+    pub config: Account<'info, Config>,
+
+    /// PDA which holds the funds used to sponsor claims and pay referral rewards of this Bakery.
+    #[account(has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  authority.key().as_ref(),
+                  TREASURY
+              ],
+              bump = treasury.bump)]
+    pub treasury: Account<'info, Treasury>,
+
+    /// Ata of the withdrawn token owned by the Treasury.
+    #[account(mut)]
+    pub treasury_token: Account<'info, TokenAccount>,
+
+    /// Token account of the same mint which receives the withdrawn tokens.
+    #[account(mut, token::mint = treasury_token.mint)]
+    pub destination: Account<'info, TokenAccount>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraws SPL tokens, such as unspent referral rewards, from an ata of the Treasury of a Bakery.
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, WithdrawTreasuryToken<'info>>,
+    amount: u64,
+) -> Result<()> {
+    let treasury = &ctx.accounts.treasury;
+    let treasury_token = &ctx.accounts.treasury_token;
+    assert_is_ata(
+        &treasury_token.to_account_info(),
+        &treasury.key(),
+        &treasury_token.mint,
+        None,
+    )?;
+    require!(treasury_token.amount >= amount, ErrorCode::InsufficientTreasuryFunds);
+
+    let authority = ctx.accounts.config.authority;
+    let treasury_seeds = &[
+        PDA_PREFIX,
+        authority.as_ref(),
+        TREASURY,
+        &[treasury.bump],
+    ];
+    let cpi_accounts = token::Transfer {
+        from: treasury_token.to_account_info(),
+        to: ctx.accounts.destination.to_account_info(),
+        authority: treasury.to_account_info(),
+    };
+    let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(context.with_signer(&[&treasury_seeds[..]]), amount)?;
+
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+use crate::errors::ErrorCode;
+use crate::state::{PDA_PREFIX, TOKEN, VESTING};
+use crate::state::{bakery::*, sprinkle::*, vesting::*};
+
+#[derive(Accounts)]
+pub struct WithdrawVested<'info> {
+    /// Account which claimed the vesting tokens, and receives them once unlocked.
+    pub user: Signer<'info>,
+
+    /// PDA which stores token approvals for a Bakery, and executes the transfer during claims.
+    pub config: Box<Account<'info, Config>>,
+
+    /// PDA which stores data about the state of a Sprinkle.
+    #[account(has_one = config,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.key().as_ref(),
+                  &tag.uid.to_le_bytes()
+              ],
+              bump = tag.bump)]
+    pub tag: Box<Account<'info, Tag>>,
+
+    /// PDA which tracks the vesting schedule of one of the user's claims of this Sprinkle.
+    #[account(mut,
+              has_one = user,
+              has_one = tag,
+              has_one = rent_payer,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.key().as_ref(),
+                  &tag.uid.to_le_bytes(),
+                  user.key().as_ref(),
+                  VESTING,
+                  &VestingEscrow::schedule_seed(vesting_escrow.season, vesting_escrow.claim_offset)
+              ],
+              bump = vesting_escrow.bump)]
+    pub vesting_escrow: Box<Account<'info, VestingEscrow>>,
+
+    /// Token account holding the user's vesting tokens, owned by the VestingEscrow PDA.
+    #[account(mut,
+              seeds = [
+                  PDA_PREFIX,
+                  config.authority.key().as_ref(),
+                  &tag.uid.to_le_bytes(),
+                  user.key().as_ref(),
+                  VESTING,
+                  &VestingEscrow::schedule_seed(vesting_escrow.season, vesting_escrow.claim_offset),
+                  TOKEN
+              ],
+              bump = vesting_escrow.token_bump)]
+    pub vesting_token: Box<Account<'info, TokenAccount>>,
+
+    /// Token account of the user which receives the unlocked tokens.
+    #[account(mut,
+              token::mint = vesting_escrow.token_mint,
+              token::authority = user)]
+    pub user_token: Box<Account<'info, TokenAccount>>,
+
+    /// The account which paid the rent of the VestingEscrow, either the claim's payer or the Bakery Treasury.
+    /// Receives the rent of the VestingEscrow and its token account once every token has been withdrawn.
+    /// CHECK: Verified as the VestingEscrow's rent_payer, and only receives its rent.
+    #[account(mut)]
+    pub rent_payer: UncheckedAccount<'info>,
+
+    /// SPL Token Program, required for transferring tokens.
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'a, 'b, 'c, 'info>(
+    ctx: Context<'a, 'b, 'c, 'info, WithdrawVested<'info>>,
+) -> Result<()> {
+    let config = &ctx.accounts.config;
+    let tag = &ctx.accounts.tag;
+    let user = &ctx.accounts.user;
+    let vesting_escrow = &mut ctx.accounts.vesting_escrow;
+
+    // Work out how many tokens have unlocked since the last withdrawal.
+    let unlocked_amount = vesting_escrow.unlocked_amount(Clock::get()?.unix_timestamp)?;
+    let amount = unlocked_amount
+        .checked_sub(vesting_escrow.withdrawn_amount)
+        .ok_or(ErrorCode::NumericalOverflowError)?;
+    require!(amount > 0, ErrorCode::NothingToWithdraw);
+
+    let user_key = user.key();
+    let schedule_seed = VestingEscrow::schedule_seed(vesting_escrow.season, vesting_escrow.claim_offset);
+    let vesting_seeds = &[
+        PDA_PREFIX,
+        config.authority.as_ref(),
+        &tag.uid.to_le_bytes(),
+        user_key.as_ref(),
+        VESTING,
+        &schedule_seed[..],
+        &[vesting_escrow.bump],
+    ];
+
+    // Release the unlocked tokens from escrow to the user.
+    let cpi_accounts = token::Transfer {
+        from: ctx.accounts.vesting_token.to_account_info(),
+        to: ctx.accounts.user_token.to_account_info(),
+        authority: vesting_escrow.to_account_info(),
+    };
+    let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token::transfer(context.with_signer(&[&vesting_seeds[..]]), amount)?;
+
+    vesting_escrow.withdrawn_amount = unlocked_amount;
+
+    // Once every token is out, close the escrow token account and the VestingEscrow, refunding their rent.
+    if vesting_escrow.withdrawn_amount == vesting_escrow.total_amount {
+        let cpi_accounts = token::CloseAccount {
+            account: ctx.accounts.vesting_token.to_account_info(),
+            destination: ctx.accounts.rent_payer.to_account_info(),
+            authority: vesting_escrow.to_account_info(),
+        };
+        let context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token::close_account(context.with_signer(&[&vesting_seeds[..]]))?;
+
+        vesting_escrow.close(ctx.accounts.rent_payer.to_account_info())?;
+    }
+
+    Ok(())
+}
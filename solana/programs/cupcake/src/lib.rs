@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+#[cfg(feature = "client")]
+pub mod client;
 pub mod errors;
 pub mod instructions;
 pub mod state;
@@ -29,13 +31,256 @@ pub mod cupcake {
         instructions::bake_sprinkle::handler(ctx, tag_params)
     }
 
+    /// Create a batch of new Sprinkles sharing the same parameters, with consecutive uids.
+    /// BakeryAuthority must be a signer.
+    pub fn bake_tag_batch<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, BakeTagBatch<'info>>,
+        params: BakeTagBatchParams,
+    ) -> Result<()> {
+        instructions::bake_tag_batch::handler(ctx, params)
+    }
+
+    /// Bring a Sprinkle baked by an earlier program version up to the current account layout.
+    /// Does not require any signer.
+    pub fn migrate_tag<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, MigrateTag<'info>>,
+    ) -> Result<()> {
+        instructions::migrate_tag::handler(ctx)
+    }
+
+    /// Change the accounts which can approve claims on a Sprinkle, without re-baking it.
+    /// BakeryAuthority must be a signer.
+    pub fn update_tag_authority<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, UpdateTagAuthority<'info>>,
+        params: UpdateTagAuthorityParams,
+    ) -> Result<()> {
+        instructions::update_tag_authority::handler(ctx, params)
+    }
+
+    /// Issue (or re-issue) a session key which can approve a limited number of claims
+    /// on a limited set of Sprinkles, until it expires. BakeryAuthority must be a signer.
+    pub fn issue_session_key<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, IssueSessionKey<'info>>,
+        params: IssueSessionKeyParams,
+    ) -> Result<()> {
+        instructions::issue_session_key::handler(ctx, params)
+    }
+
+    /// Revoke a session key, closing its PDA. BakeryAuthority must be a signer.
+    pub fn revoke_session_key<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RevokeSessionKey<'info>>,
+    ) -> Result<()> {
+        instructions::revoke_session_key::handler(ctx)
+    }
+
+    /// Set or clear the allowlist root of a Sprinkle, keeping its claim counters.
+    /// BakeryAuthority must be a signer.
+    pub fn set_allowlist_root<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetAllowlistRoot<'info>>,
+        params: SetAllowlistRootParams,
+    ) -> Result<()> {
+        instructions::set_allowlist_root::handler(ctx, params)
+    }
+
+    /// Set the keys which must all attest to each claim of a Sprinkle, alongside its authority.
+    /// BakeryAuthority must be a signer.
+    pub fn set_combo_keys<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetComboKeys<'info>>,
+        params: SetComboKeysParams,
+    ) -> Result<()> {
+        instructions::set_combo_keys::handler(ctx, params)
+    }
+
+    /// Set the reward a Sprinkle pays referrers from the Bakery Treasury, and its referral budget.
+    /// BakeryAuthority must be a signer.
+    pub fn set_referral_reward<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetReferralReward<'info>>,
+        params: SetReferralRewardParams,
+    ) -> Result<()> {
+        instructions::set_referral_reward::handler(ctx, params)
+    }
+
+    /// Create a TagTemplate holding Sprinkle defaults, or update an existing one.
+    /// BakeryAuthority must be a signer.
+    pub fn set_tag_template<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetTagTemplate<'info>>,
+        params: SetTagTemplateParams,
+    ) -> Result<()> {
+        instructions::set_tag_template::handler(ctx, params)
+    }
+
+    /// Copy the current defaults of a TagTemplate onto a Sprinkle baked from it.
+    /// Does not require any signer.
+    pub fn sync_tag_template<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SyncTagTemplate<'info>>,
+    ) -> Result<()> {
+        instructions::sync_tag_template::handler(ctx)
+    }
+
+    /// Set or remove the program whose claim authority PDA can sign claims on a Sprinkle over CPI.
+    /// BakeryAuthority must be a signer.
+    pub fn set_authority_program<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetAuthorityProgram<'info>>,
+        params: SetAuthorityProgramParams,
+    ) -> Result<()> {
+        instructions::set_authority_program::handler(ctx, params)
+    }
+
+    /// Set or remove the program a Sprinkle invokes through the on_claim interface after each claim.
+    /// BakeryAuthority must be a signer.
+    pub fn set_claim_hook<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetClaimHook<'info>>,
+        params: SetClaimHookParams,
+    ) -> Result<()> {
+        instructions::set_claim_hook::handler(ctx, params)
+    }
+
+    /// Set the price of each claim of a Sprinkle, and the recipients its revenue is split between.
+    /// BakeryAuthority must be a signer.
+    pub fn set_claim_price<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, SetClaimPrice<'info>>,
+        params: SetClaimPriceParams,
+    ) -> Result<()> {
+        instructions::set_claim_price::handler(ctx, params)
+    }
+
+    /// Top up the Treasury which sponsors the rent costs of a Bakery's claims.
+    pub fn fund_treasury<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, FundTreasury<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::fund_treasury::handler(ctx, amount)
+    }
+
+    /// Withdraw lamports from a Bakery's Treasury. BakeryAuthority must be a signer.
+    pub fn withdraw_treasury<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, WithdrawTreasury<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_treasury::handler(ctx, amount)
+    }
+
+    /// Withdraw SPL tokens from an ata of a Bakery's Treasury. BakeryAuthority must be a signer.
+    pub fn withdraw_treasury_token<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, WithdrawTreasuryToken<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_treasury_token::handler(ctx, amount)
+    }
+
+    /// Add or refill an asset slot of a Bundle Sprinkle.
+    /// BakeryAuthority must be a signer.
+    pub fn refill_bundle_slot<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RefillBundleSlot<'info>>,
+        params: RefillBundleSlotParams,
+    ) -> Result<()> {
+        instructions::refill_bundle_slot::handler(ctx, params)
+    }
+
+    /// Create a new Quest for a Bakery, or update an existing one.
+    /// BakeryAuthority must be a signer.
+    pub fn upsert_quest<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, UpsertQuest<'info>>,
+        params: UpsertQuestParams,
+    ) -> Result<()> {
+        instructions::upsert_quest::handler(ctx, params)
+    }
+
+    /// Create a new Campaign for a Bakery, or update an existing one.
+    /// BakeryAuthority must be a signer.
+    pub fn upsert_campaign<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, UpsertCampaign<'info>>,
+        params: UpsertCampaignParams,
+    ) -> Result<()> {
+        instructions::upsert_campaign::handler(ctx, params)
+    }
+
     /// Execute the claim method of a Sprinkle for a provided account.
     /// SprinkleAuthority must be a signer.
     pub fn claim_tag<'a, 'b, 'c, 'info>(
         ctx: Context<'a, 'b, 'c, 'info, ClaimTag<'info>>,
         creator_bump: u8,
     ) -> Result<()> {
-        instructions::claim_sprinkle::handler(ctx, creator_bump)
+        instructions::claim_sprinkle::legacy_handler(ctx, creator_bump)
+    }
+
+    /// Execute the claim method of a Sprinkle for a provided account, with the claim parameters and
+    /// optional accounts that allowlists, prices, quests, campaigns, sponsorship and referrals need.
+    /// SprinkleAuthority must be a signer. The Sprinkle's authority_program can call this over CPI,
+    /// signing as SprinkleAuthority with its claim authority PDA.
+    pub fn claim_tag_v2<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ClaimTagV2<'info>>,
+        creator_bump: u8,
+        params: ClaimTagParams,
+    ) -> Result<()> {
+        instructions::claim_sprinkle::handler(ctx, creator_bump, params)
+    }
+
+    /// Commit a Raffle's draw to a future slot once its entries have closed, then draw its winners once that slot has passed.
+    /// Does not require any signer, unless the committed slot's hash has expired, when SprinkleAuthority must sign to commit again.
+    pub fn draw_raffle<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, DrawRaffle<'info>>,
+    ) -> Result<()> {
+        instructions::draw_raffle::handler(ctx)
+    }
+
+    /// Claim the prize of a winning Raffle entry, closing it.
+    /// User must be a signer.
+    pub fn claim_raffle_prize<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ClaimRafflePrize<'info>>,
+    ) -> Result<()> {
+        instructions::claim_raffle_prize::handler(ctx)
+    }
+
+    /// Close a losing Raffle entry, returning its rent.
+    /// User must be a signer.
+    pub fn close_raffle_entry<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, CloseRaffleEntry<'info>>,
+    ) -> Result<()> {
+        instructions::close_raffle_entry::handler(ctx)
+    }
+
+    /// Hand out a Sprinkle to a list of recipients without scans, sharing its claim limits and running its claim hook.
+    /// Only SingleUse1Of1, Refillable1Of1, WalletRestrictedFungible without vesting and LimitedOrOpenEdition
+    /// Sprinkles can be airdropped; every other type must be claimed through a scan.
+    /// BakeryAuthority must be a signer.
+    pub fn airdrop_from_tag<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, AirdropFromTag<'info>>,
+    ) -> Result<()> {
+        instructions::airdrop_from_tag::handler(ctx)
+    }
+
+    /// Redeem the tokens claimed to an identity into a real wallet.
+    /// SprinkleAuthority must be a signer.
+    pub fn redeem_claim_escrow<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, RedeemClaimEscrow<'info>>,
+    ) -> Result<()> {
+        instructions::redeem_claim_escrow::handler(ctx)
+    }
+
+    /// Return the tokens of an expired, unredeemed claim to an identity to the Bakery.
+    /// BakeryAuthority must be a signer.
+    pub fn reclaim_claim_escrow<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, ReclaimClaimEscrow<'info>>,
+    ) -> Result<()> {
+        instructions::reclaim_claim_escrow::handler(ctx)
+    }
+
+    /// Verify that a user has claimed a Sprinkle, as proof of presence for CheckIns.
+    /// Fails if the user has not claimed it often or recently enough.
+    pub fn verify_check_in<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, VerifyCheckIn<'info>>,
+        params: VerifyCheckInParams,
+    ) -> Result<()> {
+        instructions::verify_check_in::handler(ctx, params)
+    }
+
+    /// Withdraw the unlocked tokens from a vesting fungible Sprinkle claim, closing its escrow once empty.
+    /// The claiming user must be a signer.
+    pub fn withdraw_vested<'a, 'b, 'c, 'info>(
+        ctx: Context<'a, 'b, 'c, 'info, WithdrawVested<'info>>,
+    ) -> Result<()> {
+        instructions::withdraw_vested::handler(ctx)
     }
 
     /// Modify or create a new listing
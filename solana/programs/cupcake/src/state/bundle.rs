@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+/// Different kinds of assets a Bundle slot can hand out.
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BundleSlotKind {
+    /// A single NFT, which can be swapped for a new one once it has been claimed.
+    NonFungible,
+
+    /// A set amount of fungible tokens handed out on every claim.
+    Fungible,
+}
+
+/// A single asset handed out by every claim of a Bundle Sprinkle.
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub struct BundleSlot {
+    /// The kind of asset held in this slot.
+    pub kind: BundleSlotKind,
+
+    /// The mint address of the SPL token custodied by this slot.
+    pub token_mint: Pubkey,
+
+    /// The number of tokens transferred from this slot on each claim.
+    pub amount: u64,
+
+    /// The total amount of claims this slot has been funded for.
+    pub total_supply: u64,
+}
+
+impl BundleSlot {
+    /// The account size of a single BundleSlot.
+    pub const SIZE: usize =
+        1 +     // Kind
+        32 +    // TokenMint pubkey
+        8 +     // Amount
+        8;      // TotalSupply
+}
+
+/// PDA created for each Bundle Sprinkle.
+/// Stores the list of assets that are transferred together on every claim.
+#[account]
+pub struct Bundle {
+    /// The address of the Sprinkle this Bundle belongs to.
+    pub tag: Pubkey,
+
+    /// The assets handed out by each claim of the Sprinkle.
+    pub slots: Vec<BundleSlot>,
+
+    /// Bump value used in the PDA generation for this Bundle.
+    pub bump: u8,
+}
+
+impl Bundle {
+    /// The maximum number of slots a single Bundle can hold.
+    pub const MAX_SLOTS: usize = 5;
+
+    /// The minimum required account size for a Bundle PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Sprinkle pubkey
+        4 +     // Slots vec length
+        Bundle::MAX_SLOTS * BundleSlot::SIZE +
+        1;      // PDA bump
+
+    /// The number of claims the Bundle can serve, which is the smallest supply across its slots.
+    pub fn total_supply(&self) -> u64 {
+        self.slots
+            .iter()
+            .map(|slot| slot.total_supply)
+            .min()
+            .unwrap_or(0)
+    }
+}
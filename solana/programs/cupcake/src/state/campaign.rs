@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+/// PDA created for each Campaign of a Bakery.
+/// Lets several Sprinkles draw from one shared supply, with one shared per-user limit.
+#[account]
+pub struct Campaign {
+    /// The address of the Bakery PDA which owns this Campaign.
+    pub config: Pubkey,
+
+    /// The unique identifier for this Campaign, used in PDA generation.
+    pub id: u64,
+
+    /// The total amount of claims that can be executed across all Sprinkles of this Campaign.
+    /// If this is 0, the Campaign puts no limit on the total amount of claims.
+    pub total_supply: u64,
+
+    /// A counter tracking the current number of claims executed across all Sprinkles of this Campaign.
+    pub num_claimed: u64,
+
+    /// The total number of claims an individual user can execute across all Sprinkles of this Campaign.
+    /// If this is 0, the Campaign puts no limit on claims per user.
+    pub per_user: u64,
+
+    /// Bump value used in the PDA generation for this Campaign.
+    pub bump: u8,
+}
+
+impl Campaign {
+    /// The minimum required account size for a Campaign PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Bakery pubkey
+        8 +     // ID
+        8 +     // TotalSupply
+        8 +     // NumClaimed
+        8 +     // PerUser
+        1;      // PDA bump
+
+    /// The most a user who has already claimed `user_num_claimed` can still claim from this Campaign.
+    pub fn remaining_for_user(&self, user_num_claimed: u64) -> u64 {
+        let supply_remaining = match self.total_supply {
+            0 => u64::MAX,
+            total_supply => total_supply.saturating_sub(self.num_claimed),
+        };
+        let user_remaining = match self.per_user {
+            0 => u64::MAX,
+            per_user => per_user.saturating_sub(user_num_claimed),
+        };
+
+        std::cmp::min(supply_remaining, user_remaining)
+    }
+}
+
+/// PDA, associated with a user, created for each Campaign they claim from.
+/// Maintains a counter of the total number of claims by the user across the Campaign's Sprinkles.
+#[account]
+#[derive(Default)]
+pub struct CampaignUserInfo {
+    /// The number of claims this user has executed across the Campaign's Sprinkles.
+    pub num_claimed: u64,
+
+    /// Bump value used in the PDA generation for this CampaignUserInfo.
+    pub bump: u8,
+}
+
+impl CampaignUserInfo {
+    /// The minimum required account size for a CampaignUserInfo PDA.
+    pub const SIZE: usize =
+        8 +   // Anchor discriminator
+        8 +   // NumClaimed
+        1;    // PDA bump
+}
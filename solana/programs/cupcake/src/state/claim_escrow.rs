@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+/// PDA created for each identity that claims a Sprinkle without a wallet.
+/// Owns the claimed tokens, through its ATA, until they are redeemed into a real wallet or reclaimed by the Bakery.
+#[account]
+#[derive(Default)]
+pub struct ClaimEscrow {
+    /// The address of the Sprinkle the tokens were claimed from.
+    pub tag: Pubkey,
+
+    /// Hash of the email, phone number or other identity the tokens were claimed for.
+    pub identity_hash: [u8; 32],
+
+    /// The mint address of the tokens held by this ClaimEscrow.
+    pub token_mint: Pubkey,
+
+    /// The amount of tokens held by this ClaimEscrow.
+    pub amount: u64,
+
+    /// Unix timestamp after which the Bakery can reclaim unredeemed tokens.
+    pub expires_at: i64,
+
+    /// Bump value used in the PDA generation for this ClaimEscrow.
+    pub bump: u8,
+
+    /// The account which paid the rent of the escrow and its token account, and gets it back once they are closed.
+    pub rent_payer: Pubkey,
+}
+
+impl ClaimEscrow {
+    /// The minimum required account size for a ClaimEscrow PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Sprinkle pubkey
+        32 +    // IdentityHash
+        32 +    // TokenMint pubkey
+        8 +     // Amount
+        8 +     // ExpiresAt
+        1 +     // PDA bump
+        32;     // RentPayer pubkey
+}
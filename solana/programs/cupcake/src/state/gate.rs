@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+/// PDA created for each gate token used to pass a Sprinkle's Lock gate, keyed by the Sprinkle and gate mint.
+/// Its existence marks the token as spent for that Sprinkle, while it can still pass the gates of other Sprinkles.
+#[account]
+#[derive(Default)]
+pub struct GateLock {
+    /// The address of the Sprinkle the gate token was used on.
+    pub tag: Pubkey,
+
+    /// The user who used the gate token.
+    pub user: Pubkey,
+
+    /// Bump value used in the PDA generation for this GateLock.
+    pub bump: u8,
+}
+
+impl GateLock {
+    /// The minimum required account size for a GateLock PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Sprinkle pubkey
+        32 +    // User pubkey
+        1;      // PDA bump
+}
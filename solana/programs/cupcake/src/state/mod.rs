@@ -1,15 +1,52 @@
 pub mod bakery;
+pub mod bundle;
+pub mod campaign;
+pub mod claim_escrow;
+pub mod gate;
 pub mod marketplace;
+pub mod quest;
+pub mod raffle;
+pub mod referral;
+pub mod session_key;
 pub mod sprinkle;
+pub mod tag_template;
+pub mod treasury;
 pub mod user_info;
+pub mod vesting;
 
 pub use bakery::*;
+pub use bundle::*;
+pub use campaign::*;
+pub use claim_escrow::*;
+pub use gate::*;
 pub use marketplace::*;
+pub use quest::*;
+pub use raffle::*;
+pub use referral::*;
+pub use session_key::*;
 pub use sprinkle::*;
+pub use tag_template::*;
+pub use treasury::*;
 pub use user_info::*;
+pub use vesting::*;
 
 /// String used as the first seed for all Cupcake Protocol PDAs.
 pub const PDA_PREFIX: &[u8] = b"cupcake";
 pub const LISTING: &[u8] = b"listing";
 pub const TOKEN: &[u8] = b"token";
 pub const OFFER: &[u8] = b"offer";
+pub const BUNDLE: &[u8] = b"bundle";
+pub const VESTING: &[u8] = b"vesting";
+pub const QUEST: &[u8] = b"quest";
+pub const CAMPAIGN: &[u8] = b"campaign";
+pub const SESSION: &[u8] = b"session";
+pub const GATE: &[u8] = b"gate";
+pub const TREASURY: &[u8] = b"treasury";
+pub const ESCROW: &[u8] = b"escrow";
+pub const MINT: &[u8] = b"mint";
+pub const TEMPLATE: &[u8] = b"template";
+pub const HOOK: &[u8] = b"hook";
+pub const CLAIM_AUTHORITY: &[u8] = b"claim_authority";
+pub const REFERRAL: &[u8] = b"referral";
+pub const REFERRED: &[u8] = b"referred";
+pub const RAFFLE: &[u8] = b"raffle";
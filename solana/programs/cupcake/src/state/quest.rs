@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+
+/// PDA created for each Quest of a Bakery.
+/// Lists the member Sprinkles a user needs to claim to unlock the reward Sprinkle.
+#[account]
+pub struct Quest {
+    /// The address of the Bakery PDA which owns this Quest.
+    pub config: Pubkey,
+
+    /// The unique identifier for this Quest, used in PDA generation.
+    pub id: u64,
+
+    /// The uids of the Sprinkles which count towards this Quest.
+    /// Members can only be appended, as user progress is tracked by index.
+    pub members: Vec<u64>,
+
+    /// The uid of the Sprinkle which can only be claimed once this Quest is completed.
+    pub reward_tag: u64,
+
+    /// The number of distinct members a user needs to claim. If this is 0, all of them are required.
+    pub required: u8,
+
+    /// Bump value used in the PDA generation for this Quest.
+    pub bump: u8,
+}
+
+impl Quest {
+    /// The maximum number of member Sprinkles a single Quest can hold.
+    pub const MAX_MEMBERS: usize = 32;
+
+    /// The minimum required account size for a Quest PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Bakery pubkey
+        8 +     // ID
+        4 +     // Members vec length
+        Quest::MAX_MEMBERS * 8 +
+        8 +     // RewardTag UID
+        1 +     // Required
+        1;      // PDA bump
+
+    /// The position of a Sprinkle in this Quest's members, if it is one.
+    pub fn member_index(&self, uid: u64) -> Option<usize> {
+        self.members.iter().position(|member| *member == uid)
+    }
+
+    /// The number of distinct members a user needs to claim to complete this Quest.
+    pub fn required_claims(&self) -> u32 {
+        match self.required {
+            0 => self.members.len() as u32,
+            required => required as u32,
+        }
+    }
+}
+
+/// PDA, associated with a user, created for each Quest they make progress on.
+/// Tracks which of the Quest's members the user has claimed.
+#[account]
+#[derive(Default)]
+pub struct QuestProgress {
+    /// The address of the Quest this progress belongs to.
+    pub quest: Pubkey,
+
+    /// The account making progress on the Quest.
+    pub user: Pubkey,
+
+    /// Bitmask of the Quest members claimed by the user, indexed by their position in the Quest.
+    pub claimed: u32,
+
+    /// Bump value used in the PDA generation for this QuestProgress.
+    pub bump: u8,
+}
+
+impl QuestProgress {
+    /// The minimum required account size for a QuestProgress PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Quest pubkey
+        32 +    // User pubkey
+        4 +     // Claimed bitmask
+        1;      // PDA bump
+
+    /// Whether the user has claimed enough members to complete the given Quest.
+    pub fn is_complete(&self, quest: &Quest) -> bool {
+        self.claimed.count_ones() >= quest.required_claims()
+    }
+}
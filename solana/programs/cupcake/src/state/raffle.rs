@@ -0,0 +1,87 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak;
+use crate::state::sprinkle::*;
+
+/// PDA created for each user who enters a Raffle Sprinkle, in each of its seasons.
+/// Closed once its prize is claimed, or once it lost the draw.
+#[account]
+#[derive(Default)]
+pub struct RaffleEntry {
+    /// The address of the Raffle Sprinkle this entry is for.
+    pub tag: Pubkey,
+
+    /// The user who entered the Raffle.
+    pub user: Pubkey,
+
+    /// The order in which the user entered the Raffle, starting at 0.
+    pub index: u64,
+
+    /// The Sprinkle season the user entered in.
+    pub season: u32,
+
+    /// Bump value used in the PDA generation for this RaffleEntry.
+    pub bump: u8,
+
+    /// The account which paid the entry's rent, and gets it back once the entry is closed.
+    pub rent_payer: Pubkey,
+}
+
+impl RaffleEntry {
+    /// The minimum required account size for a RaffleEntry PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Sprinkle pubkey
+        32 +    // User pubkey
+        8 +     // Index
+        4 +     // Season
+        1 +     // PDA bump
+        32;     // RentPayer pubkey
+
+    /// The season seed of the Sprinkle this entry was made in, used in PDA generation.
+    pub fn season_seed(&self) -> Vec<u8> {
+        match self.season {
+            0 => vec![],
+            season => season.to_le_bytes().to_vec(),
+        }
+    }
+
+    /// Whether this entry won the Sprinkle's draw. Entries of past seasons never win.
+    pub fn is_winner(&self, tag: &Tag) -> bool {
+        match tag.raffle_seed {
+            Some(seed) if self.season == tag.season => {
+                raffle_rank(&seed, self.index, tag.num_entries) < tag.total_supply
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The position of an entry in the random ordering of all entries given by the draw seed.
+/// The ordering is a keyed Feistel permutation of [0, num_entries), so every entry gets a
+/// distinct rank, and the first total_supply ranks win.
+pub fn raffle_rank(seed: &[u8; 32], index: u64, num_entries: u64) -> u64 {
+    // Split the smallest power of 4 covering every index into two halves of equal width.
+    let bits = 64 - num_entries.saturating_sub(1).leading_zeros();
+    let half_bits = bits - bits / 2;
+    let mask = (1u64 << half_bits) - 1;
+
+    // Walk the permutation until it lands back inside [0, num_entries).
+    let mut rank = index;
+    loop {
+        let mut left = rank >> half_bits;
+        let mut right = rank & mask;
+        for round in 0..4u8 {
+            let hash = keccak::hashv(&[seed, &[round], &right.to_le_bytes()]).to_bytes();
+            let mut round_bytes = [0u8; 8];
+            round_bytes.copy_from_slice(&hash[..8]);
+            let next_right = left ^ (u64::from_le_bytes(round_bytes) & mask);
+            left = right;
+            right = next_right;
+        }
+        rank = (left << half_bits) | right;
+
+        if rank < num_entries {
+            return rank;
+        }
+    }
+}
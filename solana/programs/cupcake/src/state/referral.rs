@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+/// PDA created for each account referring users to a Sprinkle.
+/// Counts the referrals it was rewarded for, so per_referrer_cap can be enforced.
+#[account]
+#[derive(Default)]
+pub struct ReferrerInfo {
+    /// The address of the Sprinkle the referrals were made on.
+    pub tag: Pubkey,
+
+    /// The account which referred the users.
+    pub referrer: Pubkey,
+
+    /// The number of referrals the referrer was rewarded for.
+    pub num_referrals: u64,
+
+    /// The total reward paid to the referrer, in lamports or referral_reward_mint tokens.
+    pub rewards_paid: u64,
+
+    /// Bump value used in the PDA generation for this ReferrerInfo.
+    pub bump: u8,
+}
+
+impl ReferrerInfo {
+    /// The minimum required account size for a ReferrerInfo PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Sprinkle pubkey
+        32 +    // Referrer pubkey
+        8 +     // NumReferrals
+        8 +     // RewardsPaid
+        1;      // PDA bump
+}
+
+/// PDA created for each user whose claim paid out a referral reward on a Sprinkle.
+/// Users only ever pay out one referral reward per Sprinkle, whatever the season, so its existence blocks any other.
+#[account]
+#[derive(Default)]
+pub struct ReferralInfo {
+    /// The address of the Sprinkle the user was referred to.
+    pub tag: Pubkey,
+
+    /// The referred user.
+    pub user: Pubkey,
+
+    /// The account which was rewarded for referring the user.
+    pub referrer: Pubkey,
+
+    /// Bump value used in the PDA generation for this ReferralInfo.
+    pub bump: u8,
+}
+
+impl ReferralInfo {
+    /// The minimum required account size for a ReferralInfo PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Sprinkle pubkey
+        32 +    // User pubkey
+        32 +    // Referrer pubkey
+        1;      // PDA bump
+}
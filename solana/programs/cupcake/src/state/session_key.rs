@@ -0,0 +1,49 @@
+use anchor_lang::prelude::*;
+
+/// PDA created by a Bakery for each session key it issues.
+/// Lets a short-lived key approve a limited number of claims, on a limited set of Sprinkles.
+#[account]
+pub struct SessionKey {
+    /// The address of the Bakery PDA which issued this SessionKey.
+    pub config: Pubkey,
+
+    /// The address of the key which can sign claims, in place of a Sprinkle's tag_authority.
+    pub key: Pubkey,
+
+    /// The uids of the Sprinkles this key can approve claims for.
+    pub tags: Vec<u64>,
+
+    /// A Campaign whose Sprinkles this key can also approve claims for.
+    pub campaign: Option<Pubkey>,
+
+    /// Unix timestamp after which this key can no longer approve claims.
+    pub expires_at: i64,
+
+    /// The number of claims this key can still approve.
+    pub claim_budget: u64,
+
+    /// Bump value used in the PDA generation for this SessionKey.
+    pub bump: u8,
+}
+
+impl SessionKey {
+    /// The maximum number of Sprinkles a single SessionKey can be scoped to.
+    pub const MAX_TAGS: usize = 16;
+
+    /// The minimum required account size for a SessionKey PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Bakery pubkey
+        32 +    // Key pubkey
+        4 +     // Tags vec length
+        SessionKey::MAX_TAGS * 8 +
+        33 +    // Campaign
+        8 +     // ExpiresAt
+        8 +     // ClaimBudget
+        1;      // PDA bump
+
+    /// Whether this key is scoped to the Sprinkle with the given uid and campaign.
+    pub fn covers(&self, uid: u64, campaign: Option<Pubkey>) -> bool {
+        self.tags.contains(&uid) || (self.campaign.is_some() && self.campaign == campaign)
+    }
+}
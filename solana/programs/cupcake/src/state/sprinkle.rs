@@ -1,4 +1,6 @@
+use std::convert::TryFrom;
 use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
 
 /// Different types of claim methods that can be assigned to a Sprinkle.
 #[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, Debug)]
@@ -24,6 +26,19 @@ pub enum TagType {
 
     /// Acts as a Refillable1Of1 for ProgrammableNonFungible tokens (pNFTs)
     ProgrammableUnique,
+
+    /// Transfers every asset slot of the Sprinkle's Bundle to each claimer.
+    Bundle,
+
+    /// Transfers a single token to each claimer, in exchange for an input token the claimer hands in.
+    TradeIn,
+
+    /// Moves no assets, only records each claimer's scans as proof of presence.
+    CheckIn,
+
+    /// Registers each claimer's scan as an entry, then hands a set amount of fungible tokens
+    /// to each of total_supply winners picked by a draw once entries close.
+    Raffle,
 }
 
 // Type of vault state
@@ -39,6 +54,102 @@ pub enum VaultState {
     InTransit,
 }
 
+/// What happens to a claimer's gate token when they pass a Sprinkle's gate.
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GateAction {
+    /// The claimer only has to hold the gate token.
+    Hold,
+
+    /// The gate token can only be used once to pass this Sprinkle's gate, tracked by a GateLock PDA.
+    /// Only collection gates can lock, since a single gate mint could only ever let one claimer through.
+    Lock,
+
+    /// The gate token is burnt by the claim.
+    Burn,
+}
+
+/// A holding requirement claimers must meet before they can claim a Sprinkle.
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub struct TagGate {
+    /// The mint address (or collection mint address) of the token claimers must hold.
+    pub mint: Pubkey,
+
+    /// If this is true, any NFT verified as part of the mint collection is accepted.
+    pub is_collection: bool,
+
+    /// The number of tokens claimers must hold.
+    pub amount: u64,
+
+    /// What happens to the gate token when the claimer passes the gate.
+    pub action: GateAction,
+}
+
+impl TagGate {
+    /// The account size of a single TagGate.
+    pub const SIZE: usize =
+        32 +    // Mint pubkey
+        1 +     // Is collection?
+        8 +     // Amount
+        1;      // Action
+
+    /// Checks that the gate asks for a positive amount, and that a Lock gate is a collection gate asking for a
+    /// single token, since a GateLock tracks one member of the collection.
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.amount > 0 && (self.action != GateAction::Lock || (self.is_collection && self.amount == 1)),
+            ErrorCode::InvalidTagGate
+        );
+
+        Ok(())
+    }
+}
+
+/// How the price of a Sprinkle's claims changes as it gets claimed.
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub enum PriceCurve {
+    /// Every claim costs claim_price.
+    Flat,
+
+    /// Every claim adds increment to the price of the next one.
+    Linear { increment: u64 },
+
+    /// Every claim multiplies the price of the next one by (1 + growth_bps / 10000).
+    Exponential { growth_bps: u16 },
+}
+
+/// A recipient of a share of a Sprinkle's claim revenue.
+#[derive(AnchorSerialize, AnchorDeserialize, Copy, Clone, PartialEq, Eq, Debug)]
+pub struct RevenueSplit {
+    /// The wallet receiving this share.
+    pub recipient: Pubkey,
+
+    /// The share of each claim price sent to the recipient, in basis points.
+    pub share_bps: u16,
+}
+
+impl RevenueSplit {
+    /// The account size of a single RevenueSplit.
+    pub const SIZE: usize =
+        32 +    // Recipient pubkey
+        2;      // ShareBps
+}
+
+/// Arguments of the on_claim instruction every claim hook program implements.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ClaimHookArgs {
+    /// The Sprinkle which was claimed.
+    pub tag: Pubkey,
+
+    /// The account which received the claim.
+    pub user: Pubkey,
+
+    /// The amount of tokens (or claims) the claim counted for.
+    pub amount: u64,
+
+    /// The Sprinkle's num_claimed before this claim, within its current season.
+    pub claim_index: u64,
+}
+
 /// PDA created for each Sprinkle.
 /// Stores information about the assigned NFT/Candy Machine/etc and claim method.
 /// Maintains a counter of the total number of claims executed.
@@ -98,6 +209,138 @@ pub struct Tag {
     /// If vaulted, who can move this token around remotely.
     /// Memcmp-able by frontend to find tokens I own.
     pub vault_authority: Option<Pubkey>,
+
+    /// The mint address (or verified collection) of the token claimers must hand in, for TradeIns.
+    pub input_mint: Pubkey,
+
+    /// If this is true, any NFT verified as part of the input_mint collection is accepted.
+    pub input_is_collection: bool,
+
+    /// The amount of input tokens claimers must hand in on each claim.
+    pub input_amount: u64,
+
+    /// If this is true, input tokens will be burnt instead of being sent to the Bakery authority.
+    pub input_burn: bool,
+
+    /// Number of seconds after each claim before any of its tokens unlock, for vesting fungibles.
+    pub vesting_cliff: i64,
+
+    /// Number of seconds after each claim until all of its tokens are unlocked.
+    /// If this is 0, claimed fungible tokens are transferred outright instead of vesting.
+    pub vesting_duration: i64,
+
+    /// Unix timestamp of the latest claim executed from this Sprinkle.
+    pub last_claimed_at: i64,
+
+    /// Length in seconds of the period over which per_period is enforced. If this is 0, there is no period limit.
+    pub claim_period: i64,
+
+    /// The number of claims an individual user can execute from this Sprinkle within one claim_period.
+    pub per_period: u64,
+
+    /// The address of the Quest this Sprinkle is a member or the reward of, if any.
+    pub quest: Option<Pubkey>,
+
+    /// The address of the Campaign whose supply and per-user limit this Sprinkle shares, if any.
+    pub campaign: Option<Pubkey>,
+
+    /// The current season of this Sprinkle. Re-bakes can start a new season, which resets
+    /// num_claimed and gives every user a fresh UserInfo, keeping the previous ones around.
+    pub season: u32,
+
+    /// Additional accounts which can sign to approve claims on this Sprinkle, alongside tag_authority.
+    pub additional_authorities: Vec<Pubkey>,
+
+    /// The holding requirement claimers must meet, if any.
+    pub gate: Option<TagGate>,
+
+    /// Root of the Merkle tree of users allowed to claim this Sprinkle, if any.
+    /// Each leaf is the keccak hash of the user's pubkey, optionally followed by their allocation.
+    pub allowlist_root: Option<[u8; 32]>,
+
+    /// The price of each claim, in claim_price_mint tokens (or lamports if unset). Set to 0 for free claims.
+    pub claim_price: u64,
+
+    /// The mint address of the SPL token claims are paid in. If unset, claims are paid in SOL.
+    pub claim_price_mint: Option<Pubkey>,
+
+    /// If this is true, the user pays the claim price instead of the transaction payer.
+    pub user_pays_price: bool,
+
+    /// The recipients the claim price is split between. Their shares add up to 10000 basis points.
+    pub revenue_splits: Vec<RevenueSplit>,
+
+    /// How the claim price rises with num_claimed, starting from claim_price.
+    pub price_curve: PriceCurve,
+
+    /// The total lamports the Bakery Treasury can spend reimbursing the rent costs of this Sprinkle's claims.
+    /// Set to 0 to not sponsor claims.
+    pub sponsorship_limit: u64,
+
+    /// A counter tracking the lamports the Bakery Treasury has spent on this Sprinkle's claims.
+    pub sponsored_lamports: u64,
+
+    /// Number of seconds tokens claimed to an identity stay redeemable before the Bakery can reclaim them.
+    /// Set to 0 to disable claims to an identity.
+    pub escrow_duration: i64,
+
+    /// A counter tracking the number of NFT mints the program has created for this Sprinkle's CandyMachineDrop claims.
+    /// Used as the edition number in the PDA generation of the next mint.
+    pub num_minted: u64,
+
+    /// The TagTemplate this Sprinkle was baked from, if any. Template changes can be synced onto it.
+    pub template: Option<Pubkey>,
+
+    /// The program invoked through the on_claim interface after each claim, if any.
+    pub claim_hook: Option<Pubkey>,
+
+    /// The number of accounts the claim hook takes, on top of the hook program and hook authority.
+    pub num_claim_hook_accounts: u8,
+
+    /// The program whose claim authority PDA can sign claims on this Sprinkle over CPI, if any.
+    pub authority_program: Option<Pubkey>,
+
+    /// The claim authority PDA of authority_program, with seed [CLAIM_AUTHORITY, tag.key().as_ref()].
+    pub program_authority: Option<Pubkey>,
+
+    /// The reward paid from the Bakery Treasury to the referrer of each user's first claim,
+    /// in referral_reward_mint tokens (or lamports if unset). Set to 0 to disable referral rewards.
+    pub referral_reward: u64,
+
+    /// The mint address of the SPL token referral rewards are paid in. If unset, rewards are paid in SOL.
+    pub referral_reward_mint: Option<Pubkey>,
+
+    /// The total referral rewards this Sprinkle can pay out.
+    pub referral_budget: u64,
+
+    /// A counter tracking the referral rewards this Sprinkle has paid out.
+    pub referral_spent: u64,
+
+    /// The number of referrals a single referrer can be rewarded for. Set to 0 for no limit.
+    pub per_referrer_cap: u64,
+
+    /// Unix timestamp after which a Raffle takes no more entries, and can be drawn.
+    pub raffle_ends_at: i64,
+
+    /// The amount of tokens each Raffle winner receives.
+    pub raffle_prize: u64,
+
+    /// A counter tracking the number of entries in the Raffle's current season.
+    pub num_entries: u64,
+
+    /// The seed the Raffle was drawn with, picking its winners. Unset until the draw.
+    pub raffle_seed: Option<[u8; 32]>,
+
+    /// The slot whose hash draws the Raffle, committed to once entries close. Zero until then.
+    pub raffle_draw_slot: u64,
+
+    /// Chip keys (or other Sprinkles' authorities) which must all attest to each claim, alongside the tag_authority.
+    /// Combo claims only count towards this Sprinkle, never towards the Sprinkles of the individual chips.
+    pub combo_keys: Vec<Pubkey>,
+
+    /// The layout version of this Sprinkle's account. Sprinkles baked before versioning read as 0,
+    /// and are brought up to Tag::VERSION by migrate_tag.
+    pub version: u8,
 }
 
 impl Tag {
@@ -120,5 +363,242 @@ impl Tag {
         32 +    // HotPotato location pubkey
         1 +     // Vaulted
         33 +    // VaultAuthority
+        32 +    // InputMint pubkey
+        1 +     // Input is collection?
+        8 +     // InputAmount
+        1 +     // Input burn?
+        8 +     // VestingCliff
+        8 +     // VestingDuration
+        8 +     // LastClaimedAt
+        8 +     // ClaimPeriod
+        8 +     // PerPeriod
+        33 +    // Quest
+        33 +    // Campaign
+        4 +     // Season
+        4 +     // AdditionalAuthorities vec length
+        Tag::MAX_ADDITIONAL_AUTHORITIES * 32 +
+        1 + TagGate::SIZE + // Gate
+        33 +    // AllowlistRoot
+        8 +     // ClaimPrice
+        33 +    // ClaimPriceMint
+        1 +     // User pays price?
+        4 +     // RevenueSplits vec length
+        Tag::MAX_REVENUE_SPLITS * RevenueSplit::SIZE +
+        9 +     // PriceCurve
+        8 +     // SponsorshipLimit
+        8 +     // SponsoredLamports
+        8 +     // EscrowDuration
+        8 +     // NumMinted
+        33 +    // Template
+        33 +    // ClaimHook
+        1 +     // NumClaimHookAccounts
+        33 +    // AuthorityProgram
+        33 +    // ProgramAuthority
+        8 +     // ReferralReward
+        33 +    // ReferralRewardMint
+        8 +     // ReferralBudget
+        8 +     // ReferralSpent
+        8 +     // PerReferrerCap
+        8 +     // RaffleEndsAt
+        8 +     // RafflePrize
+        8 +     // NumEntries
+        33 +    // RaffleSeed
+        8 +     // RaffleDrawSlot
+        4 +     // ComboKeys vec length
+        Tag::MAX_COMBO_KEYS * 32 +
+        1 +     // Version
         16; // ~ Padding ~
 }
+
+impl Tag {
+    /// The current layout version of Sprinkle accounts.
+    pub const VERSION: u8 = 1;
+
+    /// How many slots past the commit a Raffle's draw slot lies, so its hash is unknown at commit time.
+    pub const RAFFLE_DRAW_DELAY_SLOTS: u64 = 10;
+
+    /// The maximum number of additional authorities a Sprinkle can hold.
+    pub const MAX_ADDITIONAL_AUTHORITIES: usize = 3;
+
+    /// The maximum number of recipients a Sprinkle's claim revenue can be split between.
+    pub const MAX_REVENUE_SPLITS: usize = 5;
+
+    /// The maximum number of accounts a Sprinkle's claim hook can take.
+    pub const MAX_CLAIM_HOOK_ACCOUNTS: u8 = 16;
+
+    /// The maximum number of combo keys a Sprinkle can require.
+    pub const MAX_COMBO_KEYS: usize = 4;
+
+    /// Checks that a claim period is either off, or lets users claim at least once within each period.
+    pub fn validate_claim_period(claim_period: i64, per_period: u64) -> Result<()> {
+        require!(
+            claim_period == 0 || (claim_period > 0 && per_period > 0),
+            ErrorCode::InvalidClaimPeriod
+        );
+
+        Ok(())
+    }
+
+    /// Checks that free claims need no recipients, and that paid ones split every basis point
+    /// between at most MAX_REVENUE_SPLITS recipients.
+    pub fn validate_revenue_shares(claim_price: u64, revenue_splits: &[RevenueSplit]) -> Result<()> {
+        if claim_price == 0 {
+            return Ok(());
+        }
+
+        let total_bps = revenue_splits
+            .iter()
+            .map(|split| split.share_bps as u64)
+            .sum::<u64>();
+        require!(
+            !revenue_splits.is_empty()
+                && revenue_splits.len() <= Tag::MAX_REVENUE_SPLITS
+                && total_bps == 10000,
+            ErrorCode::InvalidRevenueSplits
+        );
+
+        Ok(())
+    }
+
+    /// Whether the given account can sign to approve claims on this Sprinkle.
+    pub fn is_tag_authority(&self, key: &Pubkey) -> bool {
+        self.tag_authority == *key
+            || self.additional_authorities.contains(key)
+            || self.program_authority == Some(*key)
+    }
+
+    /// The number of fungible tokens a user can claim in one go, without exceeding
+    /// their per_user value or the Sprinkle's remaining supply.
+    pub fn fungible_claim_amount(&self, per_user: u64, user_num_claimed: u64) -> Result<u64> {
+        let user_remaining = per_user
+            .checked_sub(user_num_claimed)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+        let supply_remaining = self.total_supply
+            .checked_sub(self.num_claimed)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+
+        Ok(std::cmp::min(user_remaining, supply_remaining))
+    }
+
+    /// The number of accounts passed at the end of the claim remaining accounts for the Sprinkle's gate.
+    pub fn num_gate_accounts(&self) -> usize {
+        match self.gate {
+            Some(gate) => 2 + gate.is_collection as usize + (gate.action == GateAction::Lock) as usize,
+            None => 0,
+        }
+    }
+
+    /// The number of accounts passed in the claim remaining accounts, just before the hook accounts,
+    /// to verify the Sprinkle's combo attestations.
+    pub fn num_combo_accounts(&self) -> usize {
+        match self.combo_keys.len() {
+            0 => 0,
+            num_keys => num_keys + 1,
+        }
+    }
+
+    /// The message each combo key attests to with an Ed25519 signature, if it does not sign the claim itself.
+    /// Including the season and the user's claim count keeps attestations from being replayed for later claims,
+    /// and the expiry keeps unused attestations from being held back and submitted much later.
+    pub fn combo_message(tag: &Pubkey, user: &Pubkey, season: u32, user_num_claimed: u64, expires_at: i64) -> Vec<u8> {
+        [
+            tag.as_ref(),
+            user.as_ref(),
+            &season.to_le_bytes(),
+            &user_num_claimed.to_le_bytes(),
+            &expires_at.to_le_bytes(),
+        ].concat()
+    }
+
+    /// The number of accounts passed in the claim remaining accounts, just before the price accounts,
+    /// to run the Sprinkle's claim hook.
+    pub fn num_hook_accounts(&self) -> usize {
+        match self.claim_hook {
+            Some(_) => 2 + self.num_claim_hook_accounts as usize,
+            None => 0,
+        }
+    }
+
+    /// The number of accounts passed in the claim remaining accounts, just before the gate accounts,
+    /// to pay the Sprinkle's claim price.
+    pub fn num_price_accounts(&self) -> usize {
+        match (self.claim_price, self.claim_price_mint) {
+            (0, _) => 0,
+            (_, None) => self.revenue_splits.len(),
+            (_, Some(_)) => 1 + self.revenue_splits.len(),
+        }
+    }
+
+    /// The price of the next claim, given by the Sprinkle's price curve at its current num_claimed.
+    pub fn current_claim_price(&self) -> Result<u64> {
+        match self.price_curve {
+            PriceCurve::Flat => Ok(self.claim_price),
+
+            PriceCurve::Linear { increment } => increment
+                .checked_mul(self.num_claimed)
+                .and_then(|increase| increase.checked_add(self.claim_price))
+                .ok_or_else(|| ErrorCode::NumericalOverflowError.into()),
+
+            PriceCurve::Exponential { growth_bps } => {
+                // Fixed point exponentiation by squaring of the per-claim growth factor.
+                const SCALE: u128 = 1_000_000_000_000;
+                let mut factor = SCALE;
+                let mut base = SCALE + SCALE * growth_bps as u128 / 10000;
+                let mut exponent = self.num_claimed;
+                while exponent > 0 {
+                    if exponent & 1 == 1 {
+                        factor = factor
+                            .checked_mul(base)
+                            .ok_or(ErrorCode::NumericalOverflowError)?
+                            / SCALE;
+                    }
+                    exponent >>= 1;
+                    if exponent > 0 {
+                        base = base
+                            .checked_mul(base)
+                            .ok_or(ErrorCode::NumericalOverflowError)?
+                            / SCALE;
+                    }
+                }
+
+                let price = (self.claim_price as u128)
+                    .checked_mul(factor)
+                    .ok_or(ErrorCode::NumericalOverflowError)?
+                    / SCALE;
+                u64::try_from(price).map_err(|_| ErrorCode::NumericalOverflowError.into())
+            }
+        }
+    }
+
+    /// Splits a claim price between the Sprinkle's revenue recipients, in order.
+    /// Any rounding dust goes to the first recipient.
+    pub fn revenue_shares(&self, price: u64) -> Result<Vec<u64>> {
+        let mut shares = Vec::with_capacity(self.revenue_splits.len());
+        for split in &self.revenue_splits {
+            let share = (price as u128)
+                .checked_mul(split.share_bps as u128)
+                .ok_or(ErrorCode::NumericalOverflowError)?
+                / 10000;
+            shares.push(share as u64);
+        }
+
+        let dust = price
+            .checked_sub(shares.iter().sum())
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+        if let Some(first) = shares.first_mut() {
+            *first = first.checked_add(dust).ok_or(ErrorCode::NumericalOverflowError)?;
+        }
+
+        Ok(shares)
+    }
+
+    /// Seed appended to UserInfo PDAs, so that every season has its own per-user counters.
+    /// The first season uses an empty seed, so UserInfos from before seasons existed keep their addresses.
+    /// Those were created with a smaller layout, and are grown to UserInfo::SIZE on their next claim.
+    pub fn season_seed(&self) -> Vec<u8> {
+        match self.season {
+            0 => vec![],
+            season => season.to_le_bytes().to_vec(),
+        }
+    }
+}
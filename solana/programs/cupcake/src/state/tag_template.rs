@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use crate::state::sprinkle::*;
+
+/// PDA created by a Bakery for each set of Sprinkle defaults it reuses.
+/// Sprinkles baked from a template keep a reference to it, so later template
+/// changes can be synced to them without re-running any delegation.
+#[account]
+pub struct TagTemplate {
+    /// The address of the Bakery PDA which owns this TagTemplate.
+    pub config: Pubkey,
+
+    /// The unique identifier for this TagTemplate, used in PDA generation.
+    pub template_id: u64,
+
+    /// The claim method Sprinkles baked from this template use.
+    pub tag_type: TagType,
+
+    /// The total amount of claims that can be executed from each Sprinkle baked from this template.
+    pub num_claims: u64,
+
+    /// The total number of claims an individual user can execute from each Sprinkle.
+    pub per_user: u64,
+
+    /// Length in seconds of the period over which per_period is enforced. Set to 0 to disable period limits.
+    pub claim_period: i64,
+
+    /// The number of claims an individual user can execute from each Sprinkle within one claim_period.
+    pub per_period: u64,
+
+    /// The holding requirement claimers must meet, if any.
+    pub gate: Option<TagGate>,
+
+    /// The total lamports the Bakery Treasury can spend reimbursing the rent costs of claims, per Sprinkle.
+    pub sponsorship_limit: u64,
+
+    /// Number of seconds tokens claimed to an identity stay redeemable. Set to 0 to disable claims to an identity.
+    pub escrow_duration: i64,
+
+    /// The price of each claim, in claim_price_mint tokens (or lamports if unset). Set to 0 for free claims.
+    pub claim_price: u64,
+
+    /// The mint address of the SPL token claims are paid in. If unset, claims are paid in SOL.
+    pub claim_price_mint: Option<Pubkey>,
+
+    /// If this is true, the user pays the claim price instead of the transaction payer.
+    pub user_pays_price: bool,
+
+    /// The recipients the claim price is split between. Their shares add up to 10000 basis points.
+    pub revenue_splits: Vec<RevenueSplit>,
+
+    /// How the claim price rises with num_claimed, starting from claim_price.
+    pub price_curve: PriceCurve,
+
+    /// Bump value used in the PDA generation for this TagTemplate.
+    pub bump: u8,
+}
+
+impl TagTemplate {
+    /// The minimum required account size for a TagTemplate PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Bakery pubkey
+        8 +     // TemplateId
+        1 +     // SprinkleType
+        8 +     // NumClaims
+        8 +     // PerUser
+        8 +     // ClaimPeriod
+        8 +     // PerPeriod
+        1 + TagGate::SIZE + // Gate
+        8 +     // SponsorshipLimit
+        8 +     // EscrowDuration
+        8 +     // ClaimPrice
+        33 +    // ClaimPriceMint
+        1 +     // User pays price?
+        4 +     // RevenueSplits vec length
+        Tag::MAX_REVENUE_SPLITS * RevenueSplit::SIZE +
+        9 +     // PriceCurve
+        1;      // PDA bump
+
+    /// Copies the defaults which need no delegation changes onto a Sprinkle.
+    /// The claim method and supply are only taken from the template when baking, and minter_pays,
+    /// which decides what a CandyMachineDrop delegates, is never taken from it.
+    pub fn apply_to(&self, tag: &mut Tag) {
+        // SingleUse1Of1s and Raffles always keep a per_user of 1.
+        if tag.tag_type != TagType::SingleUse1Of1 && tag.tag_type != TagType::Raffle {
+            tag.per_user = self.per_user;
+        }
+        tag.claim_period = self.claim_period;
+        tag.per_period = self.per_period;
+        tag.gate = self.gate;
+        tag.sponsorship_limit = self.sponsorship_limit;
+        tag.escrow_duration = self.escrow_duration;
+        tag.claim_price = self.claim_price;
+        tag.claim_price_mint = self.claim_price_mint;
+        tag.user_pays_price = self.user_pays_price;
+        tag.revenue_splits = self.revenue_splits.clone();
+        tag.price_curve = self.price_curve;
+    }
+}
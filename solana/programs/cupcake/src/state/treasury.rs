@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+/// PDA created for each Bakery which sponsors claims.
+/// Holds the lamports used to reimburse relayer payers for the rent costs of claims, and to pay referral rewards.
+/// SPL referral rewards are paid from its associated token accounts.
+#[account]
+pub struct Treasury {
+    /// The address of the Bakery PDA which owns this Treasury.
+    pub config: Pubkey,
+
+    /// Bump value used in the PDA generation for this Treasury.
+    pub bump: u8,
+}
+
+impl Treasury {
+    /// The minimum required account size for a Treasury PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Bakery pubkey
+        1;      // PDA bump
+
+    /// The lamports the Treasury can spend while staying rent exempt.
+    pub fn available_lamports(info: &AccountInfo) -> Result<u64> {
+        let rent_exempt = Rent::get()?.minimum_balance(info.data_len());
+        Ok(info.lamports().saturating_sub(rent_exempt))
+    }
+}
@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 
-/// PDA, associated with a user, created for each unique Sprinkle they claim.
-/// Maintains a counter of the total number of claims by the user for that Sprinkle.
+/// PDA, associated with a user, created for each unique Sprinkle and season they claim.
+/// Maintains a counter of the total number of claims by the user for that Sprinkle season.
 #[account]
 #[derive(Default)]
 pub struct UserInfo {
@@ -10,6 +10,18 @@ pub struct UserInfo {
 
     /// Bump value used in the PDA generation for this UserInfo.
     pub bump: u8,
+
+    /// Unix timestamp of the latest claim by this user from this Sprinkle.
+    pub last_claimed_at: i64,
+
+    /// Unix timestamp at which the user's current claim period started.
+    pub period_start: i64,
+
+    /// The number of claims this user has executed within the current claim period.
+    pub period_claims: u64,
+
+    /// The season of the Sprinkle these claims were made in.
+    pub season: u32,
 }
 
 impl UserInfo {
@@ -17,5 +29,19 @@ impl UserInfo {
     pub const SIZE: usize = 
         8 +   // Anchor discriminator
         8 +   // NumClaimed
-        1;    // PDA bump
+        1 +   // PDA bump
+        8 +   // LastClaimedAt
+        8 +   // PeriodStart
+        8 +   // PeriodClaims
+        4;    // Season
+
+    /// Whether the user's current claim period has elapsed by `now`, so their next claim starts a new one.
+    pub fn period_elapsed(&self, claim_period: i64, now: i64) -> bool {
+        now >= self.period_start.saturating_add(claim_period)
+    }
+
+    /// The number of claims the user has executed within their current claim period, as of `now`.
+    pub fn current_period_claims(&self, claim_period: i64, now: i64) -> u64 {
+        if self.period_elapsed(claim_period, now) { 0 } else { self.period_claims }
+    }
 }
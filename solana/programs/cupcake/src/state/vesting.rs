@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use crate::errors::ErrorCode;
+
+/// PDA, associated with a user, created for each claim they make of a vesting fungible Sprinkle.
+/// Holds the claimed tokens in an escrow token account until they unlock.
+/// Every claim starts its own schedule, so later claims never unlock early.
+/// Closed, along with its token account, once every token has been withdrawn.
+#[account]
+#[derive(Default)]
+pub struct VestingEscrow {
+    /// The address of the Sprinkle the tokens were claimed from.
+    pub tag: Pubkey,
+
+    /// The account which can withdraw the tokens once they unlock.
+    pub user: Pubkey,
+
+    /// The mint address of the SPL token held in escrow.
+    pub token_mint: Pubkey,
+
+    /// The Sprinkle season the claim was made in.
+    pub season: u32,
+
+    /// The user's num_claimed within the season before this claim, which sets each claim's schedule apart.
+    pub claim_offset: u64,
+
+    /// Unix timestamp of the claim, when the vesting schedule started.
+    pub start_ts: i64,
+
+    /// Number of seconds after the start before any tokens unlock.
+    pub cliff: i64,
+
+    /// Number of seconds after the start until all tokens are unlocked, linearly.
+    pub duration: i64,
+
+    /// The total number of tokens claimed into this escrow.
+    pub total_amount: u64,
+
+    /// The number of tokens already withdrawn by the user.
+    pub withdrawn_amount: u64,
+
+    /// Bump value used in the PDA generation for this VestingEscrow.
+    pub bump: u8,
+
+    /// Bump value used in the PDA generation for the escrow token account.
+    pub token_bump: u8,
+
+    /// The account which paid the rent of the escrow and its token account, and gets it back once they are closed.
+    pub rent_payer: Pubkey,
+}
+
+impl VestingEscrow {
+    /// The minimum required account size for a VestingEscrow PDA.
+    pub const SIZE: usize =
+        8 +     // Anchor discriminator
+        32 +    // Sprinkle pubkey
+        32 +    // User pubkey
+        32 +    // TokenMint pubkey
+        4 +     // Season
+        8 +     // ClaimOffset
+        8 +     // StartTs
+        8 +     // Cliff
+        8 +     // Duration
+        8 +     // TotalAmount
+        8 +     // WithdrawnAmount
+        1 +     // PDA bump
+        1 +     // Token PDA bump
+        32;     // RentPayer pubkey
+
+    /// Seed appended to VestingEscrow PDAs, so that every claim has its own schedule.
+    pub fn schedule_seed(season: u32, claim_offset: u64) -> Vec<u8> {
+        [&season.to_le_bytes()[..], &claim_offset.to_le_bytes()[..]].concat()
+    }
+
+    /// The number of tokens unlocked at the given time, including those already withdrawn.
+    pub fn unlocked_amount(&self, now: i64) -> Result<u64> {
+        let elapsed = now.saturating_sub(self.start_ts);
+        if elapsed < self.cliff {
+            return Ok(0);
+        }
+        if elapsed >= self.duration {
+            return Ok(self.total_amount);
+        }
+
+        Ok((self.total_amount as u128)
+            .checked_mul(elapsed as u128)
+            .ok_or(ErrorCode::NumericalOverflowError)?
+            .checked_div(self.duration as u128)
+            .ok_or(ErrorCode::NumericalOverflowError)? as u64)
+    }
+}
@@ -1,29 +1,37 @@
 use crate::{
     errors::ErrorCode,
-    state::{Config, Listing, Offer, Tag, LISTING, OFFER, PDA_PREFIX, TOKEN},
+    state::{
+        Campaign, CampaignUserInfo, ClaimHookArgs, Config, Listing, Offer, Quest, QuestProgress, Tag, UserInfo,
+        CAMPAIGN, HOOK, LISTING, MINT, OFFER, PDA_PREFIX, QUEST, TOKEN,
+    },
 };
 use anchor_lang::{
     error,
     prelude::{
-        next_account_info, Account, AccountInfo, CpiContext, Program, Pubkey, Rent, Result,
+        next_account_info, Account, AccountDeserialize, AccountInfo, AccountMeta, AccountSerialize,
+        AnchorSerialize, CpiContext, Owner, Program, Pubkey, Rent, Result,
         SolanaSysvar, System, Sysvar, UncheckedAccount,
     },
     require,
     solana_program::{
-        hash, msg,
+        ed25519_program, hash, keccak, msg,
+        instruction::Instruction,
         program::{invoke, invoke_signed},
         program_pack::{IsInitialized, Pack},
         system_instruction,
+        sysvar::instructions::load_instruction_at_checked,
     },
-    Key, ToAccountInfo,
+    AccountsExit, Key, ToAccountInfo,
 };
 use anchor_spl::{
-    associated_token::get_associated_token_address,
+    associated_token::get_associated_token_address_with_program_id,
     token::{self, Mint, Token},
 };
 use arrayref::array_ref;
 use mpl_token_metadata::{
-    instruction::{freeze_delegated_account, thaw_delegated_account},
+    instruction::{
+        freeze_delegated_account, mint_new_edition_from_master_edition_via_token, thaw_delegated_account,
+    },
     state::{Creator, Metadata, TokenMetadataAccount},
 };
 use spl_token::instruction::initialize_account2;
@@ -34,6 +42,11 @@ pub const OUR_FEES: u16 = 50;
 // Placeholder
 pub const OUR_ADDRESS: &str = "B3JML1kMs1dRTTEWKqP5uwoTzaKAjA3gVMUFf1vTRk2U";
 
+/// SPL Token-2022 Program.
+pub mod token_2022 {
+    anchor_lang::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+
 /// Checks if two PublicKeys are equal.
 pub fn assert_keys_equal(key1: Pubkey, key2: Pubkey) -> Result<()> {
     if key1 != key2 {
@@ -43,18 +56,22 @@ pub fn assert_keys_equal(key1: Pubkey, key2: Pubkey) -> Result<()> {
     }
 }
 
-/// Checks if a provided account is an Associated Token Account.
+/// Checks if a provided account is an Associated Token Account, of either SPL Token or Token-2022.
 pub fn assert_is_ata(
     ata: &AccountInfo,
     wallet: &Pubkey,
     mint: &Pubkey,
     delegate: Option<&Pubkey>,
 ) -> Result<spl_token::state::Account> {
-    assert_owned_by(ata, &spl_token::id())?;
-    let ata_account: spl_token::state::Account = assert_initialized(ata)?;
+    let ata_account: spl_token::state::Account = if *ata.owner == token_2022::ID {
+        assert_initialized_token_2022(ata)?
+    } else {
+        assert_owned_by(ata, &spl_token::id())?;
+        assert_initialized(ata)?
+    };
     assert_keys_equal(ata_account.owner, *wallet)?;
     assert_keys_equal(ata_account.mint, mint.key())?;
-    assert_keys_equal(get_associated_token_address(wallet, mint), *ata.key)?;
+    assert_keys_equal(get_associated_token_address_with_program_id(wallet, mint, ata.owner), *ata.key)?;
     if delegate.is_none() {
         require!(
             ata_account.delegate.is_none(),
@@ -88,6 +105,19 @@ pub fn assert_initialized<T: Pack + IsInitialized>(account_info: &AccountInfo) -
     }
 }
 
+/// Checks if the provided Token-2022 account or mint has already been initialized, reading the base state
+/// it shares with SPL Token and ignoring any extensions after it.
+pub fn assert_initialized_token_2022<T: Pack + IsInitialized>(account_info: &AccountInfo) -> Result<T> {
+    let data = account_info.data.borrow();
+    require!(data.len() >= T::LEN, ErrorCode::Uninitialized);
+    let account = T::unpack_from_slice(&data[..T::LEN])?;
+    if !account.is_initialized() {
+        Err(error!(ErrorCode::Uninitialized))
+    } else {
+        Ok(account)
+    }
+}
+
 /// Grabs an NFT's update authority address from the raw account state.
 pub fn grab_update_authority<'a>(metadata: &AccountInfo<'a>) -> Result<Pubkey> {
     let data = metadata.data.borrow();
@@ -114,6 +144,95 @@ pub fn sighash(namespace: &str, name: &str) -> [u8; 8] {
     sighash
 }
 
+/// Verifies a Merkle proof of `leaf` against `root`, hashing each pair of nodes in sorted order.
+pub fn verify_merkle_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == *root
+}
+
+/// Whether an Ed25519 program instruction of this transaction verifies a signature by `key` over `message`.
+/// Only signatures whose key, signature and message all sit in the verifying instruction itself count.
+pub fn has_ed25519_attestation(
+    instructions_sysvar: &AccountInfo,
+    key: &Pubkey,
+    message: &[u8],
+) -> Result<bool> {
+    let mut index = 0;
+    while let Ok(instruction) = load_instruction_at_checked(index, instructions_sysvar) {
+        index += 1;
+        if instruction.program_id != ed25519_program::ID {
+            continue;
+        }
+
+        // Instruction data starts with the number of signatures and a padding byte,
+        // followed by 14 bytes of offsets per signature.
+        let data = &instruction.data;
+        let num_signatures = *data.first().unwrap_or(&0) as usize;
+        for i in 0..num_signatures {
+            let offsets = match data.get(2 + i * 14..16 + i * 14) {
+                Some(offsets) => offsets,
+                None => break,
+            };
+            let read_u16 = |at: usize| u16::from_le_bytes([offsets[at], offsets[at + 1]]);
+            let (signature_ix, public_key_offset, public_key_ix) = (read_u16(2), read_u16(4) as usize, read_u16(6));
+            let (message_offset, message_size, message_ix) = (read_u16(8) as usize, read_u16(10) as usize, read_u16(12));
+            if signature_ix != u16::MAX || public_key_ix != u16::MAX || message_ix != u16::MAX {
+                continue;
+            }
+
+            if data.get(public_key_offset..public_key_offset + 32) == Some(key.as_ref())
+                && data.get(message_offset..message_offset + message_size) == Some(message)
+            {
+                return Ok(true);
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+/// Invokes a Sprinkle's claim hook through the on_claim interface.
+/// hook_accounts holds the hook program, then the hook authority PDA (which signs the
+/// call, so hooks can tell it came from a claim), then the hook's own accounts.
+/// Only the hook authority signs, so hooks can not act on behalf of the claim's signers.
+pub fn invoke_claim_hook<'a>(
+    hook_accounts: &[AccountInfo<'a>],
+    args: ClaimHookArgs,
+    hook_authority_seeds: &[&[u8]],
+) -> Result<()> {
+    let hook_program = &hook_accounts[0];
+    let hook_authority = &hook_accounts[1];
+
+    let mut accounts = vec![AccountMeta::new_readonly(hook_authority.key(), true)];
+    for account in &hook_accounts[2..] {
+        accounts.push(match account.is_writable {
+            true => AccountMeta::new(account.key(), false),
+            false => AccountMeta::new_readonly(account.key(), false),
+        });
+    }
+
+    let mut data = sighash("global", "on_claim").to_vec();
+    data.extend(args.try_to_vec()?);
+
+    invoke_signed(
+        &Instruction {
+            program_id: hook_program.key(),
+            accounts,
+            data,
+        },
+        hook_accounts,
+        &[hook_authority_seeds],
+    )?;
+
+    Ok(())
+}
+
 /// Create account almost from scratch, lifted from
 /// https://github.com/solana-labs/solana-program-library/tree/master/associated-token-account/program/src/processor.rs#L51-L98
 #[inline(always)]
@@ -162,6 +281,106 @@ pub fn create_or_allocate_account_raw<'a>(
     Ok(())
 }
 
+/// Reallocates an account owned by this program to the given size if it is smaller,
+/// topping up its rent from the payer. The added space is zeroed.
+pub fn grow_program_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &Program<'a, System>,
+    rent: &Sysvar<'a, Rent>,
+    size: usize,
+) -> Result<()> {
+    if account.data_len() >= size {
+        return Ok(());
+    }
+
+    let required_lamports = rent
+        .minimum_balance(size)
+        .saturating_sub(account.lamports());
+    if required_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, required_lamports),
+            &[
+                payer.clone(),
+                account.clone(),
+                system_program.to_account_info(),
+            ],
+        )?;
+    }
+
+    account.realloc(size, true)?;
+
+    Ok(())
+}
+
+/// Records the lamports held by each of the given accounts that holds no data yet, once per account,
+/// so `created_accounts_rent` can later tell how much rent went into the ones that got created.
+pub fn uncreated_account_balances<'a>(
+    accounts: impl IntoIterator<Item = AccountInfo<'a>>,
+) -> Vec<(AccountInfo<'a>, u64)> {
+    let mut balances: Vec<(AccountInfo<'a>, u64)> = vec![];
+    for account in accounts {
+        if account.data_is_empty() && !balances.iter().any(|(seen, _)| seen.key == account.key) {
+            let lamports = account.lamports();
+            balances.push((account, lamports));
+        }
+    }
+
+    balances
+}
+
+/// The rent put into the accounts recorded by `uncreated_account_balances` that have been created since,
+/// counting no more than the rent exemption of each, whatever else the transaction moved around.
+pub fn created_accounts_rent(balances: &[(AccountInfo, u64)], rent: &Rent) -> u64 {
+    balances
+        .iter()
+        .filter(|(account, _)| !account.data_is_empty())
+        .map(|(account, lamports_before)| {
+            account
+                .lamports()
+                .saturating_sub(*lamports_before)
+                .min(rent.minimum_balance(account.data_len()))
+        })
+        .fold(0, u64::saturating_add)
+}
+
+/// Creates a PDA owned by this program for the given Anchor account type if it does not exist yet,
+/// or grows it to the given size if it was created with an earlier, smaller layout,
+/// then loads it along with its bump. Changes need to be persisted with `exit`.
+pub fn load_or_create_program_account<'a, T>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &Program<'a, System>,
+    rent: &Sysvar<'a, Rent>,
+    size: usize,
+    seeds: &[&[u8]],
+) -> Result<(Account<'a, T>, u8)>
+where
+    T: AccountSerialize + AccountDeserialize + Owner + Clone + Default,
+{
+    let bump = assert_derivation(&crate::ID, account, seeds)?;
+
+    if account.data_is_empty() {
+        let bump_seed = [bump];
+        let mut signer_seeds = seeds.to_vec();
+        signer_seeds.push(&bump_seed);
+        create_or_allocate_account_raw(
+            crate::ID,
+            account,
+            rent,
+            system_program,
+            payer,
+            size,
+            &signer_seeds,
+        )?;
+        T::default().try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    } else {
+        grow_program_account(account, payer, system_program, rent, size)?;
+    }
+
+    Ok((Account::try_from(account)?, bump))
+}
+
 pub fn create_program_token_account_if_not_present<'a>(
     program_account: &UncheckedAccount<'a>,
     system_program: &Program<'a, System>,
@@ -273,6 +492,51 @@ pub fn make_ata<'a>(
     Ok(())
 }
 
+/// Creates the ATA of `wallet` for `mint` if it does not exist yet, paid by `fee_payer`.
+/// The ATA is created under whichever token program owns the mint, SPL Token or Token-2022.
+#[allow(clippy::too_many_arguments)]
+pub fn make_ata_if_missing<'a>(
+    ata: &AccountInfo<'a>,
+    wallet: AccountInfo<'a>,
+    mint: Option<AccountInfo<'a>>,
+    expected_mint: &Pubkey,
+    fee_payer: AccountInfo<'a>,
+    ata_program: Option<AccountInfo<'a>>,
+    token_program: AccountInfo<'a>,
+    token_2022_program: Option<AccountInfo<'a>>,
+    system_program: AccountInfo<'a>,
+    rent: AccountInfo<'a>,
+) -> Result<()> {
+    if !ata.data_is_empty() {
+        return Ok(());
+    }
+
+    let ata_program = ata_program.ok_or(ErrorCode::MissingAtaAccounts)?;
+    let mint = mint.ok_or(ErrorCode::MissingAtaAccounts)?;
+    assert_keys_equal(*mint.key, *expected_mint)?;
+
+    let token_program = if *mint.owner == token_2022::ID {
+        let token_2022_program = token_2022_program.ok_or(ErrorCode::MissingAtaAccounts)?;
+        assert_keys_equal(*token_2022_program.key, token_2022::ID)?;
+        token_2022_program
+    } else {
+        assert_owned_by(&mint, &spl_token::id())?;
+        token_program
+    };
+
+    make_ata(
+        ata.clone(),
+        wallet,
+        mint,
+        fee_payer,
+        ata_program,
+        token_program,
+        system_program,
+        rent,
+        &[],
+    )
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn pay_creator_fees<'a>(
     remaining_accounts: &mut Iter<AccountInfo<'a>>,
@@ -620,6 +884,77 @@ pub fn empty_offer_escrow_to_seller<'a, 'b, 'c, 'info, 'd>(
     Ok(())
 }
 
+pub struct MakeProgramMintArgs<'b, 'c, 'd, 'info> {
+    pub new_token_mint: &'c AccountInfo<'info>,
+    pub user_token: &'c AccountInfo<'info>,
+    pub user: &'b AccountInfo<'info>,
+    pub config: &'b Account<'info, Config>,
+    pub payer: &'b AccountInfo<'info>,
+    pub ata_program: AccountInfo<'info>,
+    pub rent: &'b Sysvar<'info, Rent>,
+    pub system_program: &'b Program<'info, System>,
+    pub token_program: &'b Program<'info, Token>,
+    pub mint_seeds: &'d [&'d [u8]],
+    pub config_seeds: &'d [&'d [u8]; 3],
+}
+
+/// Creates a new NFT mint as a PDA, with the Bakery PDA as mint authority,
+/// and mints its single token into the user's ATA, ready for Token Metadata to turn into an edition.
+pub fn make_program_mint(args: MakeProgramMintArgs) -> Result<()> {
+    let MakeProgramMintArgs {
+        new_token_mint,
+        user_token,
+        user,
+        config,
+        payer,
+        ata_program,
+        rent,
+        system_program,
+        token_program,
+        mint_seeds,
+        config_seeds,
+    } = args;
+
+    create_or_allocate_account_raw(
+        token_program.key(),
+        new_token_mint,
+        rent,
+        system_program,
+        payer,
+        Mint::LEN,
+        mint_seeds,
+    )?;
+
+    let cpi_accounts = token::InitializeMint {
+        mint: new_token_mint.clone(),
+        rent: rent.to_account_info(),
+    };
+    let context = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    token::initialize_mint(context, 0, &config.key(), Some(&config.key()))?;
+
+    make_ata(
+        user_token.clone(),
+        user.clone(),
+        new_token_mint.clone(),
+        payer.clone(),
+        ata_program,
+        token_program.to_account_info(),
+        system_program.to_account_info(),
+        rent.to_account_info(),
+        &[],
+    )?;
+
+    let cpi_accounts = token::MintTo {
+        mint: new_token_mint.clone(),
+        to: user_token.clone(),
+        authority: config.to_account_info(),
+    };
+    let context = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+    token::mint_to(context.with_signer(&[&config_seeds[..]]), 1)?;
+
+    Ok(())
+}
+
 pub struct MoveHotPotatoArgs<'b, 'c, 'd, 'info> {
     pub token_metadata_program: &'c AccountInfo<'info>,
     pub token_mint: &'c AccountInfo<'info>,
@@ -787,3 +1122,403 @@ pub fn move_hot_potato(args: MoveHotPotatoArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// What a claim or airdrop hands out, along with the accounts needed to do so.
+pub enum SprinkleDelivery<'c, 'info> {
+    /// Prints a new Edition from the Sprinkle's MasterEdition. If the new mint is missing, it is
+    /// created as a PDA of the Sprinkle and edition number, with the Bakery PDA as mint authority.
+    Edition {
+        token_mint: &'c AccountInfo<'info>,
+        token: &'c AccountInfo<'info>,
+        metadata: &'c AccountInfo<'info>,
+        master_edition: &'c AccountInfo<'info>,
+        update_authority: &'c AccountInfo<'info>,
+        token_metadata_program: &'c AccountInfo<'info>,
+        new_token_mint: &'c AccountInfo<'info>,
+        new_metadata: &'c AccountInfo<'info>,
+        new_edition: &'c AccountInfo<'info>,
+        edition_mark_pda: &'c AccountInfo<'info>,
+        new_mint_authority: &'c AccountInfo<'info>,
+        user_new_token: Option<&'c AccountInfo<'info>>,
+    },
+
+    /// Transfers `amount` tokens of `expected_mint` from the Bakery ATA to the user's ATA,
+    /// creating the user's ATA if it is missing. Token-2022 tokens always need their mint.
+    Tokens {
+        token: &'c AccountInfo<'info>,
+        user_ata: &'c AccountInfo<'info>,
+        token_mint: Option<AccountInfo<'info>>,
+        expected_mint: Pubkey,
+        amount: u64,
+    },
+}
+
+pub struct DeliverSprinkleArgs<'b, 'c, 'd, 'info> {
+    pub delivery: SprinkleDelivery<'c, 'info>,
+    pub tag: &'b Tag,
+    pub config: &'b Account<'info, Config>,
+    pub user: &'b AccountInfo<'info>,
+    pub payer: &'b AccountInfo<'info>,
+    pub ata_program: Option<AccountInfo<'info>>,
+    pub rent: &'b Sysvar<'info, Rent>,
+    pub system_program: &'b Program<'info, System>,
+    pub token_program: &'b Program<'info, Token>,
+    pub token_2022_program: Option<AccountInfo<'info>>,
+    pub config_seeds: &'d [&'d [u8]; 3],
+}
+
+/// Hands a Sprinkle's asset to a user, shared by scans and airdrops so both deliver the same way.
+pub fn deliver_sprinkle(args: DeliverSprinkleArgs) -> Result<()> {
+    let DeliverSprinkleArgs {
+        delivery,
+        tag,
+        config,
+        user,
+        payer,
+        ata_program,
+        rent,
+        system_program,
+        token_program,
+        token_2022_program,
+        config_seeds,
+    } = args;
+
+    match delivery {
+        SprinkleDelivery::Edition {
+            token_mint,
+            token,
+            metadata,
+            master_edition,
+            update_authority,
+            token_metadata_program,
+            new_token_mint,
+            new_metadata,
+            new_edition,
+            edition_mark_pda,
+            new_mint_authority,
+            user_new_token,
+        } => {
+            // Make sure that the provided metadata accounts are legitimate.
+            let update_auth = grab_update_authority(metadata)?;
+            assert_keys_equal(update_auth, update_authority.key())?;
+            assert_keys_equal(tag.token_mint, token_mint.key())?;
+            assert_keys_equal(token_metadata_program.key(), mpl_token_metadata::ID)?;
+
+            // Grab the MasterEdition supply, and incremment it to get the new Edition number.
+            let edition = get_master_edition_supply(master_edition)?
+                .checked_add(1)
+                .ok_or(ErrorCode::NumericalOverflowError)?;
+
+            if new_token_mint.data_is_empty() {
+                let user_new_token = user_new_token.ok_or(ErrorCode::MissingAtaAccounts)?;
+                let uid = tag.uid.to_le_bytes();
+                let edition_bytes = edition.to_le_bytes();
+                let mint_bump = assert_derivation(
+                    &crate::ID,
+                    new_token_mint,
+                    &[PDA_PREFIX, config.authority.as_ref(), &uid, MINT, &edition_bytes],
+                )?;
+                require!(new_mint_authority.key() == config.key(), ErrorCode::InvalidNewMintAuthority);
+
+                make_program_mint(MakeProgramMintArgs {
+                    new_token_mint,
+                    user_token: user_new_token,
+                    user,
+                    config,
+                    payer,
+                    ata_program: ata_program.ok_or(ErrorCode::MissingAtaAccounts)?,
+                    rent,
+                    system_program,
+                    token_program,
+                    mint_seeds: &[PDA_PREFIX, config.authority.as_ref(), &uid, MINT, &edition_bytes, &[mint_bump]],
+                    config_seeds,
+                })?;
+            }
+
+            // CPI into the Token Metadata Program to print a new Edition from the MasterEdition.
+            invoke_signed(
+                &mint_new_edition_from_master_edition_via_token(
+                    token_metadata_program.key(),
+                    new_metadata.key(),
+                    new_edition.key(),
+                    master_edition.key(),
+                    new_token_mint.key(),
+                    new_mint_authority.key(),
+                    payer.key(),
+                    config.authority,
+                    token.key(),
+                    update_auth,
+                    metadata.key(),
+                    token_mint.key(),
+                    edition,
+                ),
+                &[
+                    token_metadata_program.clone(),
+                    new_metadata.clone(),
+                    new_edition.clone(),
+                    master_edition.clone(),
+                    new_token_mint.clone(),
+                    new_mint_authority.clone(),
+                    payer.clone(),
+                    config.to_account_info(),
+                    token.clone(),
+                    update_authority.clone(),
+                    metadata.clone(),
+                    token_mint.clone(),
+                    edition_mark_pda.clone(),
+                    rent.to_account_info(),
+                ],
+                &[&config_seeds[..]],
+            )?;
+        }
+
+        SprinkleDelivery::Tokens {
+            token,
+            user_ata,
+            token_mint,
+            expected_mint,
+            amount,
+        } => {
+            // Ensure both the Bakery and User ATAs are legitimate, creating the User ATA if needed.
+            make_ata_if_missing(
+                user_ata,
+                user.clone(),
+                token_mint.clone(),
+                &expected_mint,
+                payer.clone(),
+                ata_program,
+                token_program.to_account_info(),
+                token_2022_program.clone(),
+                system_program.to_account_info(),
+                rent.to_account_info(),
+            )?;
+            assert_is_ata(token, &config.authority, &expected_mint, Some(&config.key()))?;
+            assert_is_ata(user_ata, &user.key(), &expected_mint, Some(&config.key()))?;
+
+            if *token.owner == token_2022::ID {
+                // Token-2022 only supports transfers checked against the mint decimals for every mint.
+                let token_mint = token_mint.ok_or(ErrorCode::MissingToken2022Accounts)?;
+                let token_2022_program = token_2022_program.ok_or(ErrorCode::MissingToken2022Accounts)?;
+                assert_keys_equal(*token_mint.key, expected_mint)?;
+                assert_keys_equal(*token_2022_program.key, token_2022::ID)?;
+                let mint: spl_token::state::Mint = assert_initialized_token_2022(&token_mint)?;
+
+                // Token-2022 shares the SPL Token instruction layout, only the program id differs.
+                let mut ix = spl_token::instruction::transfer_checked(
+                    &spl_token::id(),
+                    token.key,
+                    token_mint.key,
+                    user_ata.key,
+                    &config.key(),
+                    &[],
+                    amount,
+                    mint.decimals,
+                )?;
+                ix.program_id = token_2022::ID;
+                invoke_signed(
+                    &ix,
+                    &[
+                        token.clone(),
+                        token_mint,
+                        user_ata.clone(),
+                        config.to_account_info(),
+                        token_2022_program,
+                    ],
+                    &[&config_seeds[..]],
+                )?;
+            } else {
+                let cpi_accounts = token::Transfer {
+                    from: token.clone(),
+                    to: user_ata.clone(),
+                    authority: config.to_account_info(),
+                };
+                let context = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+                token::transfer(context.with_signer(&[&config_seeds[..]]), amount)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Derives the hook authority of a Sprinkle's claim hook and calls the hook with it,
+/// shared by scans and airdrops. The Sprinkle's counters need to be written out first, so the hook reads them.
+pub fn run_claim_hook<'a>(
+    hook_accounts: &[AccountInfo<'a>],
+    tag: &Account<'a, Tag>,
+    authority: &Pubkey,
+    args: ClaimHookArgs,
+) -> Result<()> {
+    let claim_hook = tag.claim_hook.ok_or(ErrorCode::MissingClaimHookAccounts)?;
+    require!(hook_accounts.len() == tag.num_hook_accounts(), ErrorCode::MissingClaimHookAccounts);
+    assert_keys_equal(hook_accounts[0].key(), claim_hook)?;
+
+    let uid = tag.uid.to_le_bytes();
+    let hook_authority_bump = assert_derivation(
+        &crate::ID,
+        &hook_accounts[1],
+        &[PDA_PREFIX, authority.as_ref(), &uid, HOOK],
+    )?;
+
+    invoke_claim_hook(
+        hook_accounts,
+        args,
+        &[PDA_PREFIX, authority.as_ref(), &uid, HOOK, &[hook_authority_bump]],
+    )
+}
+
+pub struct BeginClaimArgs<'b, 'info> {
+    pub tag: &'b Tag,
+    pub config: &'b Account<'info, Config>,
+    pub user: Pubkey,
+    pub payer: &'b AccountInfo<'info>,
+    pub quest: Option<&'b Account<'info, Quest>>,
+    pub quest_progress: Option<&'b AccountInfo<'info>>,
+    pub campaign: Option<&'b Account<'info, Campaign>>,
+    pub campaign_user_info: Option<&'b AccountInfo<'info>>,
+    pub rent: &'b Sysvar<'info, Rent>,
+    pub system_program: &'b Program<'info, System>,
+}
+
+/// Does the Quest and Campaign bookkeeping every claim needs before its Sprinkle is handed out,
+/// shared by scans and airdrops so both count the same way.
+/// Records the user's progress on the Sprinkle's Quest, or, for the Quest's reward Sprinkle, ensures the user
+/// has completed it. Then loads the user's CampaignUserInfo and ensures neither the Campaign's total_supply
+/// nor its per_user value have been reached. Returns the CampaignUserInfo, to pass on to `record_claim`,
+/// along with the number of tokens the Campaign still lets the user claim.
+pub fn begin_claim<'info>(args: BeginClaimArgs<'_, 'info>) -> Result<(Option<Account<'info, CampaignUserInfo>>, u64)> {
+    let BeginClaimArgs {
+        tag,
+        config,
+        user,
+        payer,
+        quest,
+        quest_progress,
+        campaign,
+        campaign_user_info,
+        rent,
+        system_program,
+    } = args;
+
+    if let Some(quest_key) = tag.quest {
+        let quest = quest.ok_or(ErrorCode::NoQuestPresent)?;
+        let quest_progress_info = quest_progress.ok_or(ErrorCode::NoQuestPresent)?;
+        assert_keys_equal(quest.key(), quest_key)?;
+
+        let (mut quest_progress, quest_progress_bump) = load_or_create_program_account::<QuestProgress>(
+            quest_progress_info,
+            payer,
+            system_program,
+            rent,
+            QuestProgress::SIZE,
+            &[
+                PDA_PREFIX,
+                config.authority.as_ref(),
+                QUEST,
+                &quest.id.to_le_bytes(),
+                user.as_ref(),
+            ],
+        )?;
+        if quest_progress.quest == Pubkey::default() {
+            quest_progress.quest = quest.key();
+            quest_progress.user = user;
+            quest_progress.bump = quest_progress_bump;
+        }
+
+        if quest.reward_tag == tag.uid {
+            require!(quest_progress.is_complete(quest), ErrorCode::QuestNotCompleted);
+        } else if let Some(index) = quest.member_index(tag.uid) {
+            quest_progress.claimed |= 1 << index;
+        }
+        quest_progress.exit(&crate::ID)?;
+    }
+
+    let mut user_campaign_info = None;
+    let mut campaign_remaining = u64::MAX;
+    if let Some(campaign_key) = tag.campaign {
+        let campaign = campaign.ok_or(ErrorCode::NoCampaignPresent)?;
+        let campaign_user_info = campaign_user_info.ok_or(ErrorCode::NoCampaignPresent)?;
+        assert_keys_equal(campaign.key(), campaign_key)?;
+
+        let (mut user_campaign, user_campaign_bump) = load_or_create_program_account::<CampaignUserInfo>(
+            campaign_user_info,
+            payer,
+            system_program,
+            rent,
+            CampaignUserInfo::SIZE,
+            &[
+                PDA_PREFIX,
+                config.authority.as_ref(),
+                CAMPAIGN,
+                &campaign.id.to_le_bytes(),
+                user.as_ref(),
+            ],
+        )?;
+        user_campaign.bump = user_campaign_bump;
+
+        require!(
+            campaign.total_supply == 0 || campaign.num_claimed < campaign.total_supply,
+            ErrorCode::CampaignDepleted
+        );
+        require!(
+            campaign.per_user == 0 || user_campaign.num_claimed < campaign.per_user,
+            ErrorCode::CampaignClaimLimitExceeded
+        );
+        campaign_remaining = campaign.remaining_for_user(user_campaign.num_claimed);
+        user_campaign_info = Some(user_campaign);
+    }
+
+    Ok((user_campaign_info, campaign_remaining))
+}
+
+pub struct RecordClaimArgs<'b, 'info> {
+    pub tag: &'b Tag,
+    pub user_info: &'b mut Account<'info, UserInfo>,
+    pub campaign: Option<&'b mut Account<'info, Campaign>>,
+    pub campaign_user_info: Option<Account<'info, CampaignUserInfo>>,
+    pub amount: u64,
+    pub now: i64,
+}
+
+/// Counts a claim of `amount` towards the user's UserInfo, starting a new claim period if the last has elapsed,
+/// and towards the Campaign's counters, for both the Campaign and the user. Persists the UserInfo and
+/// CampaignUserInfo. Shared by scans and airdrops so both count the same way.
+pub fn record_claim(args: RecordClaimArgs) -> Result<()> {
+    let RecordClaimArgs {
+        tag,
+        user_info,
+        campaign,
+        campaign_user_info,
+        amount,
+        now,
+    } = args;
+
+    user_info.num_claimed = user_info.num_claimed
+        .checked_add(amount)
+        .ok_or(ErrorCode::NumericalOverflowError)?;
+
+    if let Some(mut user_campaign) = campaign_user_info {
+        user_campaign.num_claimed = user_campaign.num_claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+        user_campaign.exit(&crate::ID)?;
+
+        let campaign = campaign.ok_or(ErrorCode::NoCampaignPresent)?;
+        campaign.num_claimed = campaign.num_claimed
+            .checked_add(amount)
+            .ok_or(ErrorCode::NumericalOverflowError)?;
+    }
+
+    let period_claims = user_info.current_period_claims(tag.claim_period, now);
+    if user_info.period_elapsed(tag.claim_period, now) {
+        user_info.period_start = now;
+    }
+    user_info.period_claims = period_claims
+        .checked_add(1)
+        .ok_or(ErrorCode::NumericalOverflowError)?;
+    user_info.last_claimed_at = now;
+    user_info.season = tag.season;
+    user_info.exit(&crate::ID)?;
+
+    Ok(())
+}
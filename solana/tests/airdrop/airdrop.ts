@@ -0,0 +1,110 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible } from '../../wip_sdk/programmableAssets';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+import { UserInfo } from '../../wip_sdk/state/userInfo';
+
+describe('Airdrops from a Sprinkle', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const recipients = [anchor.web3.Keypair.generate().publicKey, anchor.web3.Keypair.generate().publicKey];
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+
+  const sprinkleUID = '66554433221212';
+  const checkInUID = '66554433221213';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+
+  let tokenMint: PublicKey | undefined = undefined;
+
+  const airdrop = async (uid: string, to: PublicKey[]) => {
+    const sprinkleUID = new anchor.BN(`CC${uid}`, 'hex');
+    return cupcakeProgram.methods
+      .airdropFromTag()
+      .accounts({
+        authority: admin.publicKey,
+        payer: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        tag: await Sprinkle.PDA(admin.publicKey, sprinkleUID, cupcakeProgram.programId),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+        quest: cupcakeProgram.programId,
+        campaign: cupcakeProgram.programId,
+        token2022Program: cupcakeProgram.programId,
+      } as any)
+      .remainingAccounts([
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: tokenMint, isWritable: false, isSigner: false },
+        ...(
+          await Promise.all(
+            to.map(async (recipient) => [
+              { pubkey: recipient, isWritable: false, isSigner: false },
+              {
+                pubkey: await UserInfo.PDA(admin.publicKey, sprinkleUID, recipient, cupcakeProgram.programId),
+                isWritable: true,
+                isSigner: false,
+              },
+              { pubkey: getAssociatedTokenAddressSync(tokenMint, recipient), isWritable: true, isSigner: false },
+            ])
+          )
+        ).flat(),
+      ])
+      .signers([admin])
+      .rpc();
+  };
+
+  it('Should bake a `WalletRestrictedFungible` and a `CheckIn` Sprinkle', async () => {
+    const sig = await cupcakeProgram.provider.connection.requestAirdrop(admin.publicKey, LAMPORTS_PER_SOL * 10);
+    await cupcakeProgram.provider.connection.confirmTransaction(sig, 'singleGossip');
+
+    await cupcakeProgramClient.createBakery();
+    tokenMint = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 100);
+    await cupcakeProgramClient.bakeSprinkle('walletRestrictedFungible', sprinkleUID, tokenMint, 10, 3, sprinkleAuthority, {}, [
+      { pubkey: tokenMint, isWritable: false, isSigner: false },
+      { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+    ]);
+    await cupcakeProgramClient.bakeSprinkle('checkIn', checkInUID, tokenMint, 10, 1, sprinkleAuthority, {}, []);
+  });
+
+  it('Should hand out the Sprinkle to every recipient, creating their accounts', async () => {
+    await airdrop(sprinkleUID, recipients);
+
+    for (const recipient of recipients) {
+      const balance = await cupcakeProgram.provider.connection.getTokenAccountBalance(
+        getAssociatedTokenAddressSync(tokenMint, recipient)
+      );
+      expect(balance.value.uiAmount).to.equal(3);
+    }
+    const sprinkle = await cupcakeProgram.account.tag.fetch(
+      await Sprinkle.PDA(admin.publicKey, new anchor.BN(`CC${sprinkleUID}`, 'hex'), cupcakeProgram.programId)
+    );
+    expect(sprinkle.numClaimed.toNumber()).to.equal(6);
+  });
+
+  it('Should share the claim limits of the Sprinkle', async () => {
+    let error: any = undefined;
+    try {
+      await airdrop(sprinkleUID, [recipients[0]]);
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('ClaimLimitExceeded');
+  });
+
+  it('Should refuse to airdrop a Sprinkle type which must be claimed by scan', async () => {
+    let error: any = undefined;
+    try {
+      await airdrop(checkInUID, [recipients[1]]);
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('AirdropNotSupported');
+  });
+});
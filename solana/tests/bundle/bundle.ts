@@ -0,0 +1,125 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram, PDA_PREFIX } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible, mintNFT } from '../../wip_sdk/programmableAssets';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+
+describe('`Bundle` Sprinkle', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const firstUser = anchor.web3.Keypair.generate();
+  const secondUser = anchor.web3.Keypair.generate();
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+
+  const sprinkleUID = '66554433221201';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+  const uid = new anchor.BN(`CC${sprinkleUID}`, 'hex');
+
+  let nftMint: PublicKey | undefined = undefined;
+  let fungibleMint: PublicKey | undefined = undefined;
+  let bundlePDA: PublicKey | undefined = undefined;
+
+  const refillSlot = async (slot: number, kind: any, tokenMint: PublicKey, amount: number, numClaims: number) => {
+    await cupcakeProgram.methods
+      .refillBundleSlot({ slot, kind, amount: new anchor.BN(amount), numClaims: new anchor.BN(numClaims) })
+      .accounts({
+        authority: admin.publicKey,
+        payer: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        tag: await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId),
+        bundle: bundlePDA,
+        tokenMint,
+        token: getAssociatedTokenAddressSync(tokenMint, admin.publicKey),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([admin])
+      .rpc();
+  };
+
+  const claimBundle = (user: PublicKey) =>
+    cupcakeProgramClient.claimSprinkleWith(
+      sprinkleUID,
+      user,
+      sprinkleAuthority,
+      [
+        { pubkey: bundlePDA, isWritable: false, isSigner: false },
+        ...[nftMint, fungibleMint].flatMap((mint) => [
+          { pubkey: getAssociatedTokenAddressSync(mint, admin.publicKey), isWritable: true, isSigner: false },
+          { pubkey: getAssociatedTokenAddressSync(mint, user), isWritable: true, isSigner: false },
+          { pubkey: mint, isWritable: false, isSigner: false },
+        ]),
+      ],
+      { accounts: { associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID } }
+    );
+
+  it('Should create a bakery and bake an empty `Bundle` Sprinkle', async () => {
+    for (const wallet of [admin, firstUser, secondUser]) {
+      const sig = await cupcakeProgram.provider.connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await cupcakeProgram.provider.connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+    nftMint = await mintNFT(cupcakeProgram.provider, admin, admin.publicKey, 0);
+    fungibleMint = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 1000);
+    await cupcakeProgramClient.bakeSprinkle('bundle', sprinkleUID, fungibleMint, 0, 1, sprinkleAuthority, {}, []);
+
+    const sprinklePDA = await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId);
+    bundlePDA = (
+      await PublicKey.findProgramAddress(
+        [Buffer.from(PDA_PREFIX), admin.publicKey.toBuffer(), uid.toBuffer('le', 8), Buffer.from('bundle')],
+        cupcakeProgram.programId
+      )
+    )[0];
+    const sprinkle = await cupcakeProgram.account.tag.fetch(sprinklePDA);
+    expect(sprinkle.totalSupply.toNumber()).to.equal(0);
+  });
+
+  it('Should only become claimable once every slot is funded', async () => {
+    await refillSlot(0, { nonFungible: {} }, nftMint, 1, 0);
+    await refillSlot(1, { fungible: {} }, fungibleMint, 10, 0);
+
+    let error: any = undefined;
+    try {
+      await claimBundle(firstUser.publicKey);
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('TagDepleted');
+
+    await refillSlot(1, { fungible: {} }, fungibleMint, 10, 1);
+    const sprinkle = await cupcakeProgram.account.tag.fetch(
+      await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId)
+    );
+    expect(sprinkle.totalSupply.toNumber()).to.equal(1);
+  });
+
+  it('Should hand out every slot in a single claim', async () => {
+    await claimBundle(firstUser.publicKey);
+
+    const nft = await cupcakeProgram.provider.connection.getTokenAccountBalance(
+      getAssociatedTokenAddressSync(nftMint, firstUser.publicKey)
+    );
+    expect(nft.value.uiAmount).to.equal(1);
+    const fungible = await cupcakeProgram.provider.connection.getTokenAccountBalance(
+      getAssociatedTokenAddressSync(fungibleMint, firstUser.publicKey)
+    );
+    expect(fungible.value.uiAmount).to.equal(10);
+  });
+
+  it('Should refuse claims once the least funded slot is empty', async () => {
+    let error: any = undefined;
+    try {
+      await claimBundle(secondUser.publicKey);
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('TagDepleted');
+  });
+});
@@ -0,0 +1,109 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { createAssociatedTokenAccount, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { expect } from 'chai';
+import { ClaimAuthorityExample } from '../../target/types/claim_authority_example';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram } from '../../wip_sdk/cucpakeProgram';
+import { mintNFT } from '../../wip_sdk/programmableAssets';
+import { Bakery } from '../../wip_sdk/state/bakery';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+import { UserInfo } from '../../wip_sdk/state/userInfo';
+
+describe('Claims driven over CPI by an authority program', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const user = anchor.web3.Keypair.generate();
+
+  let nftMint: PublicKey | undefined = undefined;
+  let userAta: PublicKey | undefined = undefined;
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+  const authorityProgram = anchor.workspace.ClaimAuthorityExample as Program<ClaimAuthorityExample>;
+
+  const sprinkleUID = '66554433221188';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+  const uid = new anchor.BN(`CC${sprinkleUID}`, 'hex');
+
+  const claimThroughAuthorityProgram = async () => {
+    const sprinklePDA = await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId);
+    const claimAuthority = (
+      await PublicKey.findProgramAddress(
+        [Buffer.from('claim_authority'), sprinklePDA.toBuffer()],
+        authorityProgram.programId
+      )
+    )[0];
+
+    return authorityProgram.methods
+      .claim({
+        allowlistProof: [],
+        allocation: null,
+        maxPrice: null,
+        identityHash: null,
+        comboExpiresAt: null,
+      })
+      .accounts({
+        user: user.publicKey,
+        payer: admin.publicKey,
+        config: Bakery.PDA(admin.publicKey, cupcakeProgram.programId),
+        claimAuthority,
+        tag: sprinklePDA,
+        userInfo: await UserInfo.PDA(admin.publicKey, uid, user.publicKey, cupcakeProgram.programId),
+        systemProgram: SystemProgram.programId,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: SYSVAR_RENT_PUBKEY,
+        cupcakeProgram: cupcakeProgram.programId,
+      })
+      .remainingAccounts([
+        { pubkey: getAssociatedTokenAddressSync(nftMint, admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: userAta, isWritable: true, isSigner: false },
+      ])
+      .signers([admin, user])
+      .rpc();
+  };
+
+  it('Should create a bakery and bake a `Refillable1Of1` Sprinkle', async () => {
+    for (const wallet of [admin, user]) {
+      const sig = await cupcakeProgram.provider.connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await cupcakeProgram.provider.connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+    nftMint = await mintNFT(cupcakeProgram.provider, admin, admin.publicKey, 0);
+    await cupcakeProgramClient.bakeSprinkle('refillable1Of1', sprinkleUID, nftMint, 1, 1, sprinkleAuthority);
+    userAta = await createAssociatedTokenAccount(cupcakeProgram.provider.connection, user, nftMint, user.publicKey);
+  });
+
+  it('Should refuse a claim from a program which is not registered on the Sprinkle', async () => {
+    let error: any = undefined;
+    try {
+      await claimThroughAuthorityProgram();
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('InvalidTagAuthority');
+  });
+
+  it('Should claim through the registered authority program', async () => {
+    await cupcakeProgram.methods
+      .setAuthorityProgram({ authorityProgram: authorityProgram.programId })
+      .accounts({
+        authority: admin.publicKey,
+        config: Bakery.PDA(admin.publicKey, cupcakeProgram.programId),
+        tag: await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId),
+      })
+      .signers([admin])
+      .rpc();
+
+    await claimThroughAuthorityProgram();
+
+    const prize = await cupcakeProgram.provider.connection.getTokenAccountBalance(userAta);
+    expect(prize.value.uiAmount).to.equal(1);
+
+    const sprinkle = await cupcakeProgram.account.tag.fetch(
+      await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId)
+    );
+    expect(sprinkle.numClaimed.toNumber()).to.equal(1);
+  });
+});
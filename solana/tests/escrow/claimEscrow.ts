@@ -0,0 +1,133 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  getAssociatedTokenAddressSync,
+  TOKEN_PROGRAM_ID,
+} from '@solana/spl-token';
+import { LAMPORTS_PER_SOL, PublicKey } from '@solana/web3.js';
+import { expect } from 'chai';
+import { createHash } from 'crypto';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram, PDA_PREFIX } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible } from '../../wip_sdk/programmableAssets';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+
+describe('Claims to an identity through a `ClaimEscrow`', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const user = anchor.web3.Keypair.generate();
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+
+  const sprinkleUID = '66554433221207';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+  const uid = new anchor.BN(`CC${sprinkleUID}`, 'hex');
+  const identityHash = createHash('sha256').update('baker@example.com').digest();
+
+  let tokenMint: PublicKey | undefined = undefined;
+  let claimEscrow: PublicKey | undefined = undefined;
+
+  it('Should bake a `WalletRestrictedFungible` Sprinkle claimable to an identity', async () => {
+    for (const wallet of [admin, user]) {
+      const sig = await cupcakeProgram.provider.connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await cupcakeProgram.provider.connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+    tokenMint = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 100);
+    await cupcakeProgramClient.bakeSprinkle(
+      'walletRestrictedFungible',
+      sprinkleUID,
+      tokenMint,
+      10,
+      5,
+      sprinkleAuthority,
+      { escrowDuration: new anchor.BN(3600) },
+      [
+        { pubkey: tokenMint, isWritable: false, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+      ]
+    );
+
+    claimEscrow = (
+      await PublicKey.findProgramAddress(
+        [Buffer.from(PDA_PREFIX), admin.publicKey.toBuffer(), uid.toBuffer('le', 8), Buffer.from('escrow'), identityHash],
+        cupcakeProgram.programId
+      )
+    )[0];
+  });
+
+  it('Should refuse a claim to an identity when the ClaimEscrow is not writable', async () => {
+    let error: any = undefined;
+    try {
+      await cupcakeProgramClient.claimSprinkleWith(
+        sprinkleUID,
+        claimEscrow,
+        sprinkleAuthority,
+        [
+          { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+          { pubkey: getAssociatedTokenAddressSync(tokenMint, claimEscrow, true), isWritable: true, isSigner: false },
+        ],
+        {
+          params: { identityHash: Array.from(identityHash) },
+          accounts: { associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, tokenMint },
+        }
+      );
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('ClaimEscrowNotWritable');
+  });
+
+  it('Should hold the claimed tokens in the ClaimEscrow of the identity', async () => {
+    await cupcakeProgramClient.claimSprinkleWith(
+      sprinkleUID,
+      claimEscrow,
+      sprinkleAuthority,
+      [
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, claimEscrow, true), isWritable: true, isSigner: false },
+      ],
+      {
+        params: { identityHash: Array.from(identityHash) },
+        accounts: { associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, tokenMint },
+        userWritable: true,
+      }
+    );
+
+    const escrow = await cupcakeProgram.account.claimEscrow.fetch(claimEscrow);
+    expect(escrow.amount.toNumber()).to.equal(5);
+    expect(escrow.rentPayer.toBase58()).to.equal(admin.publicKey.toBase58());
+    const held = await cupcakeProgram.provider.connection.getTokenAccountBalance(
+      getAssociatedTokenAddressSync(tokenMint, claimEscrow, true)
+    );
+    expect(held.value.uiAmount).to.equal(5);
+  });
+
+  it('Should redeem the tokens into a wallet, and close the ClaimEscrow', async () => {
+    const userToken = await createAssociatedTokenAccount(cupcakeProgram.provider.connection, user, tokenMint, user.publicKey);
+
+    await cupcakeProgram.methods
+      .redeemClaimEscrow()
+      .accounts({
+        user: user.publicKey,
+        payer: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        tagAuthority: sprinkleAuthority.publicKey,
+        tag: await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId),
+        claimEscrow,
+        escrowToken: getAssociatedTokenAddressSync(tokenMint, claimEscrow, true),
+        userToken,
+        rentPayer: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([admin, sprinkleAuthority])
+      .rpc();
+
+    const redeemed = await cupcakeProgram.provider.connection.getTokenAccountBalance(userToken);
+    expect(redeemed.value.uiAmount).to.equal(5);
+    expect(await cupcakeProgram.provider.connection.getAccountInfo(claimEscrow)).to.equal(null);
+  });
+});
@@ -0,0 +1,121 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL, PublicKey } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram, getMetadataPDA, PDA_PREFIX } from '../../wip_sdk/cucpakeProgram';
+import { mintNFT, verifyCollectionItem } from '../../wip_sdk/programmableAssets';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+
+describe('Sprinkle with a `Lock` gate', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const firstUser = anchor.web3.Keypair.generate();
+  const secondUser = anchor.web3.Keypair.generate();
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+
+  const sprinkleUID = '66554433221166';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+
+  let collectionMint: PublicKey | undefined = undefined;
+  let firstGateMint: PublicKey | undefined = undefined;
+  let secondGateMint: PublicKey | undefined = undefined;
+  let prizeMints: PublicKey[] = [];
+
+  const lockGate = (isCollection: boolean) => ({
+    gate: { mint: collectionMint, isCollection, amount: new anchor.BN(1), action: { lock: {} } },
+  });
+
+  const gateAccounts = async (user: PublicKey, gateMint: PublicKey) => {
+    const sprinklePDA = await Sprinkle.PDA(admin.publicKey, new anchor.BN(`CC${sprinkleUID}`, 'hex'), cupcakeProgram.programId);
+    const gateLock = (
+      await PublicKey.findProgramAddress(
+        [Buffer.from(PDA_PREFIX), sprinklePDA.toBuffer(), Buffer.from('gate'), gateMint.toBuffer()],
+        cupcakeProgram.programId
+      )
+    )[0];
+    return [
+      { pubkey: getAssociatedTokenAddressSync(gateMint, user), isWritable: true, isSigner: false },
+      { pubkey: gateMint, isWritable: true, isSigner: false },
+      { pubkey: await getMetadataPDA(gateMint), isWritable: false, isSigner: false },
+      { pubkey: gateLock, isWritable: true, isSigner: false },
+    ];
+  };
+
+  it('Should create a bakery and gate collection', async () => {
+    for (const wallet of [admin, firstUser, secondUser]) {
+      const sig = await cupcakeProgram.provider.connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await cupcakeProgram.provider.connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+
+    collectionMint = await mintNFT(cupcakeProgram.provider, admin, admin.publicKey, 0);
+    firstGateMint = await mintNFT(cupcakeProgram.provider, firstUser, firstUser.publicKey, 0, undefined, collectionMint);
+    secondGateMint = await mintNFT(cupcakeProgram.provider, secondUser, secondUser.publicKey, 0, undefined, collectionMint);
+    await verifyCollectionItem(cupcakeProgram.provider, admin, firstGateMint, collectionMint);
+    await verifyCollectionItem(cupcakeProgram.provider, admin, secondGateMint, collectionMint);
+
+    for (let i = 0; i < 2; i++) {
+      prizeMints.push(await mintNFT(cupcakeProgram.provider, admin, admin.publicKey, 0));
+    }
+  });
+
+  it('Should refuse to bake a `Lock` gate on a single mint', async () => {
+    let error: any = undefined;
+    try {
+      await cupcakeProgramClient.bakeSprinkle('refillable1Of1', sprinkleUID, prizeMints[0], 1, 2, sprinkleAuthority, lockGate(false));
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('InvalidTagGate');
+  });
+
+  it('Should let the first holder of the collection pass the gate', async () => {
+    await cupcakeProgramClient.bakeSprinkle('refillable1Of1', sprinkleUID, prizeMints[0], 1, 2, sprinkleAuthority, lockGate(true));
+    await cupcakeProgramClient.claimSprinkle(
+      sprinkleUID,
+      firstUser.publicKey,
+      sprinkleAuthority,
+      await gateAccounts(firstUser.publicKey, firstGateMint)
+    );
+
+    const prize = await cupcakeProgram.provider.connection.getTokenAccountBalance(
+      getAssociatedTokenAddressSync(prizeMints[0], firstUser.publicKey)
+    );
+    expect(prize.value.uiAmount).to.equal(1);
+  });
+
+  it('Should not let a locked gate token pass again', async () => {
+    await cupcakeProgramClient.bakeSprinkle('refillable1Of1', sprinkleUID, prizeMints[1], 1, 2, sprinkleAuthority, lockGate(true));
+
+    let error: any = undefined;
+    try {
+      await cupcakeProgramClient.claimSprinkle(
+        sprinkleUID,
+        firstUser.publicKey,
+        sprinkleAuthority,
+        await gateAccounts(firstUser.publicKey, firstGateMint)
+      );
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('GateTokenAlreadyUsed');
+  });
+
+  it('Should let a second holder of the collection pass the gate', async () => {
+    await cupcakeProgramClient.claimSprinkle(
+      sprinkleUID,
+      secondUser.publicKey,
+      sprinkleAuthority,
+      await gateAccounts(secondUser.publicKey, secondGateMint)
+    );
+
+    const prize = await cupcakeProgram.provider.connection.getTokenAccountBalance(
+      getAssociatedTokenAddressSync(prizeMints[1], secondUser.publicKey)
+    );
+    expect(prize.value.uiAmount).to.equal(1);
+  });
+});
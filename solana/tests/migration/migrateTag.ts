@@ -0,0 +1,58 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram, SYSVAR_RENT_PUBKEY } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+
+describe('`migrate_tag`', async () => {
+  const payer = anchor.web3.Keypair.generate();
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+
+  // Loaded from tests/fixtures/unversioned_tag.json, see Anchor.toml.
+  const unversionedTag = new PublicKey('57FbwsWp9bmen5ELfdSw7M5fFcRAq3Xqy7geGvHkkq5p');
+
+  const migrateTag = () =>
+    cupcakeProgram.methods
+      .migrateTag()
+      .accounts({
+        payer: payer.publicKey,
+        tag: unversionedTag,
+        systemProgram: SystemProgram.programId,
+        rent: SYSVAR_RENT_PUBKEY,
+      })
+      .signers([payer])
+      .rpc();
+
+  it('Should fund test wallets', async () => {
+    let sig = await cupcakeProgram.provider.connection.requestAirdrop(payer.publicKey, LAMPORTS_PER_SOL * 10);
+    await cupcakeProgram.provider.connection.confirmTransaction(sig, 'singleGossip');
+  });
+
+  it('Should zero the key left behind by a cleared vault_authority', async () => {
+    await migrateTag();
+
+    const tag = await cupcakeProgram.account.tag.fetch(unversionedTag);
+    expect(tag.version).to.equal(1);
+    expect(tag.tagType).to.deep.equal({ hotPotato: {} });
+    expect(tag.totalSupply.toNumber()).to.equal(1);
+    expect(tag.vaultAuthority).to.be.null;
+    expect(tag.inputMint.toBase58()).to.equal(PublicKey.default.toBase58());
+    expect(tag.inputIsCollection).to.be.false;
+    expect(tag.quest).to.be.null;
+    expect(tag.comboKeys).to.be.empty;
+
+    const tagAccount = await cupcakeProgram.provider.connection.getAccountInfo(unversionedTag);
+    expect(tagAccount.data.length).to.be.greaterThan(293);
+  });
+
+  it('Should not migrate a Sprinkle twice', async () => {
+    let error;
+    try {
+      await migrateTag();
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('TagAlreadyMigrated');
+  });
+});
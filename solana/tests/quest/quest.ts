@@ -0,0 +1,154 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL, PublicKey, SystemProgram } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram, PDA_PREFIX } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible } from '../../wip_sdk/programmableAssets';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+
+describe('Quest of Sprinkles', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const user = anchor.web3.Keypair.generate();
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+  const memberUIDs = ['66554433221203', '66554433221204'];
+  const rewardUID = '66554433221205';
+  const otherRewardUID = '66554433221206';
+  const mints: { [uid: string]: PublicKey } = {};
+
+  const toUID = (uid: string) => new anchor.BN(`CC${uid}`, 'hex');
+
+  const questPDA = async (id: number) =>
+    (
+      await PublicKey.findProgramAddress(
+        [Buffer.from(PDA_PREFIX), admin.publicKey.toBuffer(), Buffer.from('quest'), new anchor.BN(id).toBuffer('le', 8)],
+        cupcakeProgram.programId
+      )
+    )[0];
+
+  const questProgressPDA = async (id: number) =>
+    (
+      await PublicKey.findProgramAddress(
+        [
+          Buffer.from(PDA_PREFIX),
+          admin.publicKey.toBuffer(),
+          Buffer.from('quest'),
+          new anchor.BN(id).toBuffer('le', 8),
+          user.publicKey.toBuffer(),
+        ],
+        cupcakeProgram.programId
+      )
+    )[0];
+
+  const upsertQuest = async (id: number, members: string[], reward: string) =>
+    cupcakeProgram.methods
+      .upsertQuest({ id: new anchor.BN(id), members: members.map(toUID), rewardTag: toUID(reward), required: 0 })
+      .accounts({
+        authority: admin.publicKey,
+        payer: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        quest: await questPDA(id),
+        rewardTag: await Sprinkle.PDA(admin.publicKey, toUID(reward), cupcakeProgram.programId),
+        systemProgram: SystemProgram.programId,
+        previousRewardTag: cupcakeProgram.programId,
+      } as any)
+      .remainingAccounts(
+        await Promise.all(
+          members.map(async (uid) => ({
+            pubkey: await Sprinkle.PDA(admin.publicKey, toUID(uid), cupcakeProgram.programId),
+            isWritable: true,
+            isSigner: false,
+          }))
+        )
+      )
+      .signers([admin])
+      .rpc();
+
+  const claim = async (uid: string) =>
+    cupcakeProgramClient.claimSprinkleWith(
+      uid,
+      user.publicKey,
+      sprinkleAuthority,
+      [
+        { pubkey: getAssociatedTokenAddressSync(mints[uid], admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(mints[uid], user.publicKey), isWritable: true, isSigner: false },
+      ],
+      {
+        accounts: {
+          quest: await questPDA(1),
+          questProgress: await questProgressPDA(1),
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMint: mints[uid],
+        },
+      }
+    );
+
+  it('Should bake the member and reward Sprinkles of a Quest', async () => {
+    for (const wallet of [admin, user]) {
+      const sig = await cupcakeProgram.provider.connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await cupcakeProgram.provider.connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+    for (const uid of [...memberUIDs, rewardUID, otherRewardUID]) {
+      mints[uid] = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 10);
+      await cupcakeProgramClient.bakeSprinkle('walletRestrictedFungible', uid, mints[uid], 10, 1, sprinkleAuthority, {}, [
+        { pubkey: mints[uid], isWritable: false, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(mints[uid], admin.publicKey), isWritable: true, isSigner: false },
+      ]);
+    }
+
+    await upsertQuest(1, memberUIDs, rewardUID);
+    const reward = await cupcakeProgram.account.tag.fetch(
+      await Sprinkle.PDA(admin.publicKey, toUID(rewardUID), cupcakeProgram.programId)
+    );
+    expect(reward.quest.toBase58()).to.equal((await questPDA(1)).toBase58());
+  });
+
+  it('Should not hand out the reward before the Quest is completed', async () => {
+    await claim(memberUIDs[0]);
+
+    let error: any = undefined;
+    try {
+      await claim(rewardUID);
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('QuestNotCompleted');
+  });
+
+  it('Should hand out the reward once every member is claimed', async () => {
+    await claim(memberUIDs[1]);
+    await claim(rewardUID);
+
+    const reward = await cupcakeProgram.provider.connection.getTokenAccountBalance(
+      getAssociatedTokenAddressSync(mints[rewardUID], user.publicKey)
+    );
+    expect(reward.value.uiAmount).to.equal(1);
+  });
+
+  it('Should refuse to link a member Sprinkle into a second Quest', async () => {
+    let error: any = undefined;
+    try {
+      await upsertQuest(2, [memberUIDs[0]], otherRewardUID);
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('TagInAnotherQuest');
+  });
+
+  it('Should refuse to make the reward of one Quest the reward of another', async () => {
+    let error: any = undefined;
+    try {
+      await upsertQuest(2, [otherRewardUID], rewardUID);
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('TagInAnotherQuest');
+  });
+});
@@ -0,0 +1,174 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { createAssociatedTokenAccount, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SYSVAR_SLOT_HASHES_PUBKEY } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram, PDA_PREFIX } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible } from '../../wip_sdk/programmableAssets';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+
+describe('`Raffle` Sprinkle', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const entrants = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+  const connection = cupcakeProgram.provider.connection;
+
+  const sprinkleUID = '66554433221211';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+  const uid = new anchor.BN(`CC${sprinkleUID}`, 'hex');
+  const rafflePrize = 10;
+
+  let tokenMint: PublicKey | undefined = undefined;
+  let raffleEndsAt = 0;
+
+  const sleep = (ms: number) => new Promise((resolve) => setTimeout(resolve, ms));
+
+  const sprinklePDA = () => Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId);
+
+  const raffleEntryPDA = async (user: PublicKey) =>
+    (
+      await PublicKey.findProgramAddress(
+        [Buffer.from(PDA_PREFIX), admin.publicKey.toBuffer(), uid.toBuffer('le', 8), Buffer.from('raffle'), user.toBuffer()],
+        cupcakeProgram.programId
+      )
+    )[0];
+
+  const enter = async (user: Keypair) =>
+    cupcakeProgramClient.claimSprinkleWith(sprinkleUID, user.publicKey, sprinkleAuthority, [
+      { pubkey: await raffleEntryPDA(user.publicKey), isWritable: true, isSigner: false },
+    ]);
+
+  // The Sprinkle authority is only needed once a committed slot expires, but signing with it
+  // keeps back to back draws from being the same transaction.
+  const draw = async (withAuthority = false) =>
+    cupcakeProgram.methods
+      .drawRaffle()
+      .accounts({
+        config: cupcakeProgramClient.bakeryPDA,
+        tag: await sprinklePDA(),
+        slotHashes: SYSVAR_SLOT_HASHES_PUBKEY,
+        tagAuthority: withAuthority ? sprinkleAuthority.publicKey : cupcakeProgram.programId,
+      } as any)
+      .signers(withAuthority ? [sprinkleAuthority] : [])
+      .rpc();
+
+  const expectError = async (promise: Promise<any>, code: string) => {
+    let error: any = undefined;
+    try {
+      await promise;
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal(code);
+  };
+
+  it('Should bake a `Raffle` Sprinkle with a single winner', async () => {
+    for (const wallet of [admin, ...entrants]) {
+      const sig = await connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+    tokenMint = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 100);
+
+    raffleEndsAt = (await connection.getBlockTime(await connection.getSlot())) + 10;
+    await cupcakeProgramClient.bakeSprinkle(
+      'raffle',
+      sprinkleUID,
+      tokenMint,
+      1,
+      1,
+      sprinkleAuthority,
+      { raffleEndsAt: new anchor.BN(raffleEndsAt), rafflePrize: new anchor.BN(rafflePrize) },
+      [
+        { pubkey: tokenMint, isWritable: false, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+      ]
+    );
+  });
+
+  it('Should take a single entry per user while the Raffle is open', async () => {
+    for (const entrant of entrants) {
+      await enter(entrant);
+    }
+    await expectError(enter(entrants[0]), 'AlreadyEnteredRaffle');
+
+    const sprinkle = await cupcakeProgram.account.tag.fetch(await sprinklePDA());
+    expect(sprinkle.numEntries.toNumber()).to.equal(entrants.length);
+  });
+
+  it('Should not be drawn before its entries close', async () => {
+    await expectError(draw(), 'RaffleStillOpen');
+  });
+
+  it('Should commit the draw to a future slot, then draw once that slot has passed', async () => {
+    while ((await connection.getBlockTime(await connection.getSlot())) < raffleEndsAt) {
+      await sleep(1000);
+    }
+
+    await draw();
+    let sprinkle = await cupcakeProgram.account.tag.fetch(await sprinklePDA());
+    expect(sprinkle.raffleDrawSlot.toNumber()).to.be.greaterThan(0);
+    expect(sprinkle.raffleSeed).to.equal(null);
+
+    await expectError(draw(true), 'RaffleDrawSlotPending');
+
+    while ((await connection.getSlot()) <= sprinkle.raffleDrawSlot.toNumber()) {
+      await sleep(500);
+    }
+    await draw();
+    sprinkle = await cupcakeProgram.account.tag.fetch(await sprinklePDA());
+    expect(sprinkle.raffleSeed).to.not.equal(null);
+
+    await expectError(draw(true), 'RaffleAlreadyDrawn');
+  });
+
+  it('Should pay out the winner, and let the loser close their entry', async () => {
+    const results = [];
+    for (const entrant of entrants) {
+      const userToken = await createAssociatedTokenAccount(connection, entrant, tokenMint, entrant.publicKey);
+      try {
+        await cupcakeProgram.methods
+          .claimRafflePrize()
+          .accounts({
+            user: entrant.publicKey,
+            config: cupcakeProgramClient.bakeryPDA,
+            tag: await sprinklePDA(),
+            raffleEntry: await raffleEntryPDA(entrant.publicKey),
+            rentPayer: admin.publicKey,
+            token: getAssociatedTokenAddressSync(tokenMint, admin.publicKey),
+            userToken,
+            tokenProgram: TOKEN_PROGRAM_ID,
+          })
+          .signers([entrant])
+          .rpc();
+        results.push('won');
+      } catch (e) {
+        expect(e?.error?.errorCode?.code).to.equal('NotARaffleWinner');
+        results.push('lost');
+      }
+    }
+    expect(results.filter((result) => result === 'won').length).to.equal(1);
+
+    const winner = entrants[results.indexOf('won')];
+    const loser = entrants[results.indexOf('lost')];
+    const prize = await connection.getTokenAccountBalance(getAssociatedTokenAddressSync(tokenMint, winner.publicKey));
+    expect(prize.value.uiAmount).to.equal(rafflePrize);
+
+    await cupcakeProgram.methods
+      .closeRaffleEntry()
+      .accounts({
+        user: loser.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        tag: await sprinklePDA(),
+        raffleEntry: await raffleEntryPDA(loser.publicKey),
+        rentPayer: admin.publicKey,
+      })
+      .signers([loser])
+      .rpc();
+    expect(await connection.getAccountInfo(await raffleEntryPDA(loser.publicKey))).to.equal(null);
+  });
+});
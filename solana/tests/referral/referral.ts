@@ -0,0 +1,131 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram, PDA_PREFIX } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible } from '../../wip_sdk/programmableAssets';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+
+describe('Referral rewards', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const user = anchor.web3.Keypair.generate();
+  const secondUser = anchor.web3.Keypair.generate();
+  const referrer = anchor.web3.Keypair.generate();
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+  const connection = cupcakeProgram.provider.connection;
+
+  const sprinkleUID = '66554433221210';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+  const uid = new anchor.BN(`CC${sprinkleUID}`, 'hex');
+  const referralReward = LAMPORTS_PER_SOL / 100;
+
+  let tokenMint: PublicKey | undefined = undefined;
+
+  const treasury = PublicKey.findProgramAddressSync(
+    [Buffer.from(PDA_PREFIX), admin.publicKey.toBuffer(), Buffer.from('treasury')],
+    cupcakeProgram.programId
+  )[0];
+
+  const referralPDA = async (seed: string, key: PublicKey) =>
+    (
+      await PublicKey.findProgramAddress(
+        [Buffer.from(PDA_PREFIX), admin.publicKey.toBuffer(), uid.toBuffer('le', 8), Buffer.from(seed), key.toBuffer()],
+        cupcakeProgram.programId
+      )
+    )[0];
+
+  const claimReferred = async (claimer: Keypair, referredBy: PublicKey) =>
+    cupcakeProgramClient.claimSprinkleWith(
+      sprinkleUID,
+      claimer.publicKey,
+      sprinkleAuthority,
+      [
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, claimer.publicKey), isWritable: true, isSigner: false },
+      ],
+      {
+        accounts: {
+          treasury,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMint,
+          referrer: referredBy,
+          referrerInfo: await referralPDA('referral', referredBy),
+          referralInfo: await referralPDA('referred', claimer.publicKey),
+        },
+      }
+    );
+
+  const bake = (newSeason: boolean) =>
+    cupcakeProgramClient.bakeSprinkle('walletRestrictedFungible', sprinkleUID, tokenMint, 5, 1, sprinkleAuthority, { newSeason }, [
+      { pubkey: tokenMint, isWritable: false, isSigner: false },
+      { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+    ]);
+
+  it('Should bake a Sprinkle rewarding referrals from the Treasury', async () => {
+    for (const wallet of [admin, user, secondUser]) {
+      const sig = await connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+    await cupcakeProgram.methods
+      .fundTreasury(new anchor.BN(LAMPORTS_PER_SOL))
+      .accounts({
+        payer: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        treasury,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    tokenMint = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 10);
+    await bake(false);
+
+    await cupcakeProgram.methods
+      .setReferralReward({
+        referralReward: new anchor.BN(referralReward),
+        referralRewardMint: null,
+        referralBudget: new anchor.BN(referralReward * 5),
+        perReferrerCap: new anchor.BN(0),
+      })
+      .accounts({
+        authority: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        tag: await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId),
+      })
+      .signers([admin])
+      .rpc();
+  });
+
+  it('Should reward the referrer of a user's first claim', async () => {
+    await claimReferred(user, referrer.publicKey);
+
+    expect(await connection.getBalance(referrer.publicKey)).to.equal(referralReward);
+    const referrerInfo = await cupcakeProgram.account.referrerInfo.fetch(await referralPDA('referral', referrer.publicKey));
+    expect(referrerInfo.numReferrals.toNumber()).to.equal(1);
+  });
+
+  it('Should not reward a later claim of the same user, even in a new season', async () => {
+    await bake(true);
+    await claimReferred(user, referrer.publicKey);
+
+    expect(await connection.getBalance(referrer.publicKey)).to.equal(referralReward);
+    const sprinkle = await cupcakeProgram.account.tag.fetch(await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId));
+    expect(sprinkle.referralSpent.toNumber()).to.equal(referralReward);
+  });
+
+  it('Should refuse a user referring themselves', async () => {
+    let error: any = undefined;
+    try {
+      await claimReferred(secondUser, secondUser.publicKey);
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('SelfReferral');
+  });
+});
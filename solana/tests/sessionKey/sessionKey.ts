@@ -0,0 +1,129 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram, PDA_PREFIX } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible } from '../../wip_sdk/programmableAssets';
+
+describe('Claims approved by a session key', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const users = [anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate(), anchor.web3.Keypair.generate()];
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+  const connection = cupcakeProgram.provider.connection;
+
+  const scopedUID = '66554433221214';
+  const otherUID = '66554433221215';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+  const key = anchor.web3.Keypair.generate();
+
+  let tokenMint: PublicKey | undefined = undefined;
+
+  const sessionKeyPDA = PublicKey.findProgramAddressSync(
+    [Buffer.from(PDA_PREFIX), admin.publicKey.toBuffer(), Buffer.from('session'), key.publicKey.toBuffer()],
+    cupcakeProgram.programId
+  )[0];
+
+  const issue = async (claimBudget: number, expiresAt: number) =>
+    cupcakeProgram.methods
+      .issueSessionKey({
+        tags: [new anchor.BN(`CC${scopedUID}`, 'hex')],
+        campaign: null,
+        expiresAt: new anchor.BN(expiresAt),
+        claimBudget: new anchor.BN(claimBudget),
+      })
+      .accounts({
+        authority: admin.publicKey,
+        payer: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        key: key.publicKey,
+        sessionKey: sessionKeyPDA,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+  const claimWithKey = async (uid: string, user: Keypair, sessionKey: PublicKey = sessionKeyPDA) =>
+    cupcakeProgramClient.claimSprinkleWith(
+      uid,
+      user.publicKey,
+      key,
+      [
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, user.publicKey), isWritable: true, isSigner: false },
+      ],
+      { accounts: { sessionKey } }
+    );
+
+  const expectError = async (promise: Promise<any>, code: string) => {
+    let error: any = undefined;
+    try {
+      await promise;
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal(code);
+  };
+
+  const now = async () => connection.getBlockTime(await connection.getSlot());
+
+  it('Should bake two Sprinkles and issue a key scoped to one of them', async () => {
+    const sig = await connection.requestAirdrop(admin.publicKey, LAMPORTS_PER_SOL * 10);
+    await connection.confirmTransaction(sig, 'singleGossip');
+
+    await cupcakeProgramClient.createBakery();
+    tokenMint = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 100);
+    for (const uid of [scopedUID, otherUID]) {
+      await cupcakeProgramClient.bakeSprinkle('walletRestrictedFungible', uid, tokenMint, 10, 1, sprinkleAuthority, {}, [
+        { pubkey: tokenMint, isWritable: false, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+      ]);
+    }
+
+    await issue(1, (await now()) + 3600);
+    const sessionKey = await cupcakeProgram.account.sessionKey.fetch(sessionKeyPDA);
+    expect(sessionKey.key.toBase58()).to.equal(key.publicKey.toBase58());
+    expect(sessionKey.claimBudget.toNumber()).to.equal(1);
+  });
+
+  it('Should refuse claims on a Sprinkle outside of the scope of the key', async () => {
+    await expectError(claimWithKey(otherUID, users[0]), 'SessionKeyOutOfScope');
+  });
+
+  it('Should approve claims until the budget of the key is spent', async () => {
+    await claimWithKey(scopedUID, users[0]);
+
+    const balance = await connection.getTokenAccountBalance(getAssociatedTokenAddressSync(tokenMint, users[0].publicKey));
+    expect(balance.value.uiAmount).to.equal(1);
+    const sessionKey = await cupcakeProgram.account.sessionKey.fetch(sessionKeyPDA);
+    expect(sessionKey.claimBudget.toNumber()).to.equal(0);
+
+    await expectError(claimWithKey(scopedUID, users[1]), 'SessionKeyBudgetExhausted');
+  });
+
+  it('Should refuse claims once the key has expired', async () => {
+    await issue(5, (await now()) - 1);
+    await expectError(claimWithKey(scopedUID, users[1]), 'SessionKeyExpired');
+  });
+
+  it('Should revoke the key, which then no longer passes as a tag authority', async () => {
+    await issue(5, (await now()) + 3600);
+    await claimWithKey(scopedUID, users[1]);
+
+    await cupcakeProgram.methods
+      .revokeSessionKey()
+      .accounts({
+        authority: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        sessionKey: sessionKeyPDA,
+      })
+      .signers([admin])
+      .rpc();
+    expect(await connection.getAccountInfo(sessionKeyPDA)).to.equal(null);
+
+    await expectError(claimWithKey(scopedUID, users[2], cupcakeProgram.programId), 'InvalidTagAuthority');
+  });
+});
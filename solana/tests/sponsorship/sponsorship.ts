@@ -0,0 +1,127 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram, PDA_PREFIX } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible } from '../../wip_sdk/programmableAssets';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+import { UserInfo } from '../../wip_sdk/state/userInfo';
+
+describe('Sponsored claims', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const user = anchor.web3.Keypair.generate();
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+  const connection = cupcakeProgram.provider.connection;
+
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+  const freeUID = '66554433221208';
+  const pricedUID = '66554433221209';
+  const claimPrice = LAMPORTS_PER_SOL / 10;
+  const mints: { [uid: string]: PublicKey } = {};
+
+  const treasury = PublicKey.findProgramAddressSync(
+    [Buffer.from(PDA_PREFIX), admin.publicKey.toBuffer(), Buffer.from('treasury')],
+    cupcakeProgram.programId
+  )[0];
+
+  const toUID = (uid: string) => new anchor.BN(`CC${uid}`, 'hex');
+
+  const claim = (uid: string, payer: Keypair, priceAccounts: PublicKey[] = []) =>
+    cupcakeProgramClient.claimSprinkleWith(
+      uid,
+      user.publicKey,
+      sprinkleAuthority,
+      [
+        { pubkey: getAssociatedTokenAddressSync(mints[uid], admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(mints[uid], user.publicKey), isWritable: true, isSigner: false },
+        ...priceAccounts.map((pubkey) => ({ pubkey, isWritable: true, isSigner: false })),
+      ],
+      {
+        accounts: { treasury, associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, tokenMint: mints[uid] },
+        payer,
+      }
+    );
+
+  // The rent of the UserInfo and ATA the user's first claim of a Sprinkle creates.
+  const createdRent = async (uid: string) =>
+    (await connection.getBalance(await UserInfo.PDA(admin.publicKey, toUID(uid), user.publicKey, cupcakeProgram.programId))) +
+    (await connection.getBalance(getAssociatedTokenAddressSync(mints[uid], user.publicKey)));
+
+  const sponsoredLamports = async (uid: string) =>
+    (
+      await cupcakeProgram.account.tag.fetch(await Sprinkle.PDA(admin.publicKey, toUID(uid), cupcakeProgram.programId))
+    ).sponsoredLamports.toNumber();
+
+  it('Should fund the Treasury and bake sponsored Sprinkles', async () => {
+    for (const wallet of [admin, user]) {
+      const sig = await connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+    await cupcakeProgram.methods
+      .fundTreasury(new anchor.BN(LAMPORTS_PER_SOL))
+      .accounts({
+        payer: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        treasury,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([admin])
+      .rpc();
+
+    for (const uid of [freeUID, pricedUID]) {
+      mints[uid] = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 10);
+      await cupcakeProgramClient.bakeSprinkle(
+        'walletRestrictedFungible',
+        uid,
+        mints[uid],
+        10,
+        1,
+        sprinkleAuthority,
+        { sponsorshipLimit: new anchor.BN(LAMPORTS_PER_SOL) },
+        [
+          { pubkey: mints[uid], isWritable: false, isSigner: false },
+          { pubkey: getAssociatedTokenAddressSync(mints[uid], admin.publicKey), isWritable: true, isSigner: false },
+        ]
+      );
+    }
+  });
+
+  it('Should reimburse the payer for the rent of the accounts the claim creates', async () => {
+    const treasuryBefore = await connection.getBalance(treasury);
+    await claim(freeUID, admin);
+
+    const reimbursed = await sponsoredLamports(freeUID);
+    expect(reimbursed).to.equal(await createdRent(freeUID));
+    expect(treasuryBefore - (await connection.getBalance(treasury))).to.equal(reimbursed);
+  });
+
+  it('Should not reimburse the claim price a user pays as their own payer', async () => {
+    await cupcakeProgram.methods
+      .setClaimPrice({
+        claimPrice: new anchor.BN(claimPrice),
+        claimPriceMint: null,
+        userPaysPrice: true,
+        revenueSplits: [{ recipient: admin.publicKey, shareBps: 10000 }],
+        priceCurve: { flat: {} },
+      })
+      .accounts({
+        authority: admin.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        tag: await Sprinkle.PDA(admin.publicKey, toUID(pricedUID), cupcakeProgram.programId),
+      })
+      .signers([admin])
+      .rpc();
+
+    const adminBefore = await connection.getBalance(admin.publicKey);
+    await claim(pricedUID, user, [admin.publicKey]);
+
+    expect((await connection.getBalance(admin.publicKey)) - adminBefore).to.equal(claimPrice);
+    expect(await sponsoredLamports(pricedUID)).to.equal(await createdRent(pricedUID));
+  });
+});
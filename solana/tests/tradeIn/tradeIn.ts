@@ -0,0 +1,104 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { ASSOCIATED_TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL, PublicKey } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible } from '../../wip_sdk/programmableAssets';
+
+describe('`TradeIn` Sprinkle', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const user = anchor.web3.Keypair.generate();
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+
+  const sprinkleUID = '66554433221202';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+
+  let prizeMint: PublicKey | undefined = undefined;
+  let ticketMint: PublicKey | undefined = undefined;
+
+  const tradeIn = (userSigner?: anchor.web3.Keypair) =>
+    cupcakeProgramClient.claimSprinkleWith(
+      sprinkleUID,
+      user.publicKey,
+      sprinkleAuthority,
+      [
+        { pubkey: getAssociatedTokenAddressSync(prizeMint, admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(prizeMint, user.publicKey), isWritable: true, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(ticketMint, user.publicKey), isWritable: true, isSigner: false },
+        { pubkey: ticketMint, isWritable: true, isSigner: false },
+        { pubkey: admin.publicKey, isWritable: false, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(ticketMint, admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
+      ],
+      {
+        accounts: { associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID, tokenMint: prizeMint },
+        userSigner,
+      }
+    );
+
+  const balance = async (mint: PublicKey, owner: PublicKey) =>
+    (await cupcakeProgram.provider.connection.getTokenAccountBalance(getAssociatedTokenAddressSync(mint, owner))).value
+      .uiAmount;
+
+  it('Should bake a `TradeIn` Sprinkle taking two tickets per claim', async () => {
+    for (const wallet of [admin, user]) {
+      const sig = await cupcakeProgram.provider.connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await cupcakeProgram.provider.connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+    prizeMint = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 100);
+    ticketMint = await mintFungible(cupcakeProgram.provider, user, user.publicKey, 5);
+
+    await cupcakeProgramClient.bakeSprinkle(
+      'tradeIn',
+      sprinkleUID,
+      prizeMint,
+      10,
+      3,
+      sprinkleAuthority,
+      { inputAmount: new anchor.BN(2), inputBurn: false },
+      [
+        { pubkey: prizeMint, isWritable: false, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(prizeMint, admin.publicKey), isWritable: true, isSigner: false },
+        { pubkey: ticketMint, isWritable: false, isSigner: false },
+      ]
+    );
+  });
+
+  it('Should refuse a trade in the user did not sign', async () => {
+    let error: any = undefined;
+    try {
+      await tradeIn();
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('UserMustSign');
+  });
+
+  it('Should take the tickets and hand out the prize', async () => {
+    await tradeIn(user);
+
+    expect(await balance(ticketMint, user.publicKey)).to.equal(3);
+    expect(await balance(ticketMint, admin.publicKey)).to.equal(2);
+    expect(await balance(prizeMint, user.publicKey)).to.equal(1);
+  });
+
+  it('Should refuse a trade in without enough tickets left', async () => {
+    await tradeIn(user);
+    expect(await balance(ticketMint, user.publicKey)).to.equal(1);
+
+    let error: any = undefined;
+    try {
+      await tradeIn(user);
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('NotEnoughTradeInTokens');
+    expect(await balance(prizeMint, user.publicKey)).to.equal(2);
+  });
+});
@@ -0,0 +1,124 @@
+import * as anchor from '@project-serum/anchor';
+import { Program } from '@project-serum/anchor';
+import { createAssociatedTokenAccount, getAssociatedTokenAddressSync, TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { LAMPORTS_PER_SOL, PublicKey } from '@solana/web3.js';
+import { expect } from 'chai';
+import { Cupcake } from '../../target/types/cupcake';
+import { CupcakeProgram, PDA_PREFIX } from '../../wip_sdk/cucpakeProgram';
+import { mintFungible } from '../../wip_sdk/programmableAssets';
+import { Sprinkle } from '../../wip_sdk/state/sprinkle';
+
+describe('Vesting `WalletRestrictedFungible` Sprinkle', async () => {
+  const admin = anchor.web3.Keypair.generate();
+  const user = anchor.web3.Keypair.generate();
+
+  const cupcakeProgram = anchor.workspace.Cupcake as Program<Cupcake>;
+  const cupcakeProgramClient = new CupcakeProgram(cupcakeProgram, admin);
+  const connection = cupcakeProgram.provider.connection;
+
+  const sprinkleUID = '66554433221216';
+  const sprinkleAuthority = anchor.web3.Keypair.generate();
+  const uid = new anchor.BN(`CC${sprinkleUID}`, 'hex');
+  const vestingCliff = 5;
+  const vestingDuration = 6;
+
+  let tokenMint: PublicKey | undefined = undefined;
+  let userToken: PublicKey | undefined = undefined;
+
+  // The first claim of the first season: season 0 as u32, then the user's num_claimed of 0 as u64.
+  const scheduleSeed = Buffer.alloc(12);
+  const vestingEscrow = PublicKey.findProgramAddressSync(
+    [Buffer.from(PDA_PREFIX), admin.publicKey.toBuffer(), uid.toBuffer('le', 8), user.publicKey.toBuffer(), Buffer.from('vesting'), scheduleSeed],
+    cupcakeProgram.programId
+  )[0];
+  const vestingToken = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from(PDA_PREFIX),
+      admin.publicKey.toBuffer(),
+      uid.toBuffer('le', 8),
+      user.publicKey.toBuffer(),
+      Buffer.from('vesting'),
+      scheduleSeed,
+      Buffer.from('token'),
+    ],
+    cupcakeProgram.programId
+  )[0];
+
+  const withdraw = async () =>
+    cupcakeProgram.methods
+      .withdrawVested()
+      .accounts({
+        user: user.publicKey,
+        config: cupcakeProgramClient.bakeryPDA,
+        tag: await Sprinkle.PDA(admin.publicKey, uid, cupcakeProgram.programId),
+        vestingEscrow,
+        vestingToken,
+        userToken,
+        rentPayer: admin.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+
+  it('Should bake a Sprinkle vesting its tokens after a cliff', async () => {
+    for (const wallet of [admin, user]) {
+      const sig = await connection.requestAirdrop(wallet.publicKey, LAMPORTS_PER_SOL * 10);
+      await connection.confirmTransaction(sig, 'singleGossip');
+    }
+
+    await cupcakeProgramClient.createBakery();
+    tokenMint = await mintFungible(cupcakeProgram.provider, admin, admin.publicKey, 100);
+    userToken = await createAssociatedTokenAccount(connection, user, tokenMint, user.publicKey);
+    await cupcakeProgramClient.bakeSprinkle(
+      'walletRestrictedFungible',
+      sprinkleUID,
+      tokenMint,
+      10,
+      4,
+      sprinkleAuthority,
+      { vestingCliff: new anchor.BN(vestingCliff), vestingDuration: new anchor.BN(vestingDuration) },
+      [
+        { pubkey: tokenMint, isWritable: false, isSigner: false },
+        { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+      ]
+    );
+  });
+
+  it('Should claim the tokens into an escrow', async () => {
+    await cupcakeProgramClient.claimSprinkleWith(sprinkleUID, user.publicKey, sprinkleAuthority, [
+      { pubkey: getAssociatedTokenAddressSync(tokenMint, admin.publicKey), isWritable: true, isSigner: false },
+      { pubkey: vestingEscrow, isWritable: true, isSigner: false },
+      { pubkey: vestingToken, isWritable: true, isSigner: false },
+      { pubkey: tokenMint, isWritable: false, isSigner: false },
+    ]);
+
+    const escrowed = await connection.getTokenAccountBalance(vestingToken);
+    expect(escrowed.value.uiAmount).to.equal(4);
+    const escrow = await cupcakeProgram.account.vestingEscrow.fetch(vestingEscrow);
+    expect(escrow.totalAmount.toNumber()).to.equal(4);
+    expect(escrow.rentPayer.toBase58()).to.equal(admin.publicKey.toBase58());
+  });
+
+  it('Should not release any tokens before the cliff', async () => {
+    let error: any = undefined;
+    try {
+      await withdraw();
+    } catch (e) {
+      error = e;
+    }
+    expect(error?.error?.errorCode?.code).to.equal('NothingToWithdraw');
+  });
+
+  it('Should release every token once vested, and close the escrow', async () => {
+    await new Promise((resolve) => setTimeout(resolve, (vestingDuration + 2) * 1000));
+
+    const rentPayerBefore = await connection.getBalance(admin.publicKey);
+    await withdraw();
+
+    const balance = await connection.getTokenAccountBalance(userToken);
+    expect(balance.value.uiAmount).to.equal(4);
+    expect(await connection.getAccountInfo(vestingEscrow)).to.equal(null);
+    expect(await connection.getAccountInfo(vestingToken)).to.equal(null);
+    expect(await connection.getBalance(admin.publicKey)).to.be.greaterThan(rentPayerBefore);
+  });
+});
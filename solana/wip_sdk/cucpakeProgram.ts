@@ -1,5 +1,5 @@
 import { Program, BN } from "@project-serum/anchor";
-import { Keypair, PublicKey, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
+import { AccountMeta, Keypair, PublicKey, SYSVAR_INSTRUCTIONS_PUBKEY } from "@solana/web3.js";
 import { Cupcake } from '../target/types/cupcake';
 import * as TokenAuth from "@metaplex-foundation/mpl-token-auth-rules"
 import * as TokenMetadata from "@metaplex-foundation/mpl-token-metadata"
@@ -57,7 +57,7 @@ export class CupcakeProgram {
         .rpc()
     }
 
-    async bakeSprinkle(sprinkleType: string, uid: string, tokenMint: PublicKey, numClaims: number, perUser: number, sprinkleAuthority: Keypair) {
+    async bakeSprinkle(sprinkleType: string, uid: string, tokenMint: PublicKey, numClaims: number, perUser: number, sprinkleAuthority: Keypair, extraParams: any = {}, remainingAccounts?: AccountMeta[]) {
       const sprinkleUID = new BN(`CC${uid}`, "hex");
       const sprinklePDA = await Sprinkle.PDA(
         this.bakeryAuthorityKeypair.publicKey, 
@@ -74,12 +74,13 @@ export class CupcakeProgram {
       const masterEditionPDA = await getMasterEditionPDA(tokenMint);
       const tokenRecordPDA = await getTokenRecordPDA(tokenMint, bakeryTokenATA);
 
+      // Fungible mints may have no metadata at all.
       const metadata = await TokenMetadata.Metadata.fromAccountAddress(
         this.program.provider.connection, 
         metadataPDA
-      );
-      const isProgrammable = !!metadata.programmableConfig
-      const hasRuleset = !!metadata.programmableConfig?.ruleSet
+      ).catch(() => null);
+      const isProgrammable = !!metadata?.programmableConfig
+      const hasRuleset = !!metadata?.programmableConfig?.ruleSet
       console.log(isProgrammable, hasRuleset, "baking")
 
       return this.program.methods
@@ -90,23 +91,38 @@ export class CupcakeProgram {
           minterPays: false,
           pricePerMint: null,
           whitelistBurn: false,
-          tagType: { [sprinkleType]: true }
+          inputIsCollection: false,
+          inputAmount: new BN(0),
+          inputBurn: false,
+          vestingCliff: new BN(0),
+          vestingDuration: new BN(0),
+          claimPeriod: new BN(0),
+          perPeriod: new BN(0),
+          gate: null,
+          sponsorshipLimit: new BN(0),
+          escrowDuration: new BN(0),
+          raffleEndsAt: new BN(0),
+          rafflePrize: new BN(0),
+          newSeason: false,
+          tagType: { [sprinkleType]: true },
+          ...extraParams,
         } as any)
         .accounts({
           authority: this.bakeryAuthorityKeypair.publicKey,
           payer: this.bakeryAuthorityKeypair.publicKey,
           config: this.bakeryPDA,
           tagAuthority: sprinkleAuthority.publicKey,
-          tag: sprinklePDA
-        })
-        .remainingAccounts([
+          tag: sprinklePDA,
+          template: this.program.programId,
+        } as any)
+        .remainingAccounts(remainingAccounts ?? [
           { pubkey: tokenMint, isWritable: false, isSigner: false },
           { pubkey: bakeryTokenATA, isWritable: true, isSigner: false },
           { pubkey: metadataPDA, isWritable: true, isSigner: false },
           { pubkey: masterEditionPDA, isWritable: false, isSigner: false },
           { pubkey: tokenRecordPDA, isWritable: true, isSigner: false },
           { 
-            pubkey: hasRuleset ? metadata.programmableConfig!.ruleSet : TokenMetadata.PROGRAM_ID, 
+            pubkey: hasRuleset ? metadata!.programmableConfig!.ruleSet : TokenMetadata.PROGRAM_ID, 
             isWritable: false, 
             isSigner: false 
           },
@@ -118,7 +134,7 @@ export class CupcakeProgram {
         .rpc()
     }
 
-    async claimSprinkle(uid: string, user: PublicKey, sprinkleAuthorityKeypair: Keypair) {
+    async claimSprinkle(uid: string, user: PublicKey, sprinkleAuthorityKeypair: Keypair, trailingAccounts: AccountMeta[] = []) {
       const sprinkleUID = new BN(`CC${uid}`, "hex");
       const sprinklePDA = await Sprinkle.PDA(
         this.bakeryAuthorityKeypair.publicKey, 
@@ -138,7 +154,8 @@ export class CupcakeProgram {
         this.bakeryAuthorityKeypair.publicKey, 
         sprinkleUID, 
         user,
-        this.program.programId
+        this.program.programId,
+        sprinkleState.season
       );
       const metadataPDA = await getMetadataPDA(sprinkleState.tokenMint);
       const masterEditionPDA = await getMasterEditionPDA(sprinkleState.tokenMint);
@@ -153,7 +170,13 @@ export class CupcakeProgram {
       const hasRuleset = !!metadata.programmableConfig?.ruleSet
 
       return this.program.methods
-      .claimTag(0)
+      .claimTagV2(0, {
+        allowlistProof: [],
+        allocation: null,
+        maxPrice: null,
+        identityHash: null,
+        comboExpiresAt: null,
+      })
       .accounts({
         user,
         authority: this.bakeryAuthorityKeypair.publicKey,
@@ -162,7 +185,22 @@ export class CupcakeProgram {
         tagAuthority: sprinkleAuthorityKeypair.publicKey,
         tag: sprinklePDA,
         userInfo: userInfoPDA,
-      })
+        // Optional accounts are read by position, with the program id standing in for a missing one.
+        quest: this.program.programId,
+        questProgress: this.program.programId,
+        campaign: this.program.programId,
+        campaignUserInfo: this.program.programId,
+        sessionKey: this.program.programId,
+        treasury: this.program.programId,
+        associatedTokenProgram: this.program.programId,
+        tokenMint: this.program.programId,
+        token2022Program: this.program.programId,
+        referrer: this.program.programId,
+        referrerInfo: this.program.programId,
+        treasuryToken: this.program.programId,
+        referrerToken: this.program.programId,
+        referralInfo: this.program.programId,
+      } as any)
       .remainingAccounts([
         // Base transfer accounts
         { pubkey: token, isWritable: true, isSigner: false },
@@ -197,6 +235,8 @@ export class CupcakeProgram {
         { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isWritable: false, isSigner: false },
         { pubkey: TokenMetadata.PROGRAM_ID, isWritable: false, isSigner: false },
         { pubkey: SYSVAR_INSTRUCTIONS_PUBKEY, isWritable: false, isSigner: false },
+        // Combo, hook, price and gate accounts
+        ...trailingAccounts,
       ])
       .preInstructions([
         createAssociatedTokenAccountInstruction(
@@ -210,5 +250,71 @@ export class CupcakeProgram {
       .rpc()
     }
 
+    // Claims a Sprinkle with the given remaining accounts, for claim methods claimSprinkle doesn't build the accounts of.
+    // Optional accounts default to missing, and are overridden through `accounts`.
+    async claimSprinkleWith(
+      uid: string,
+      user: PublicKey,
+      sprinkleAuthorityKeypair: Keypair,
+      remainingAccounts: AccountMeta[],
+      options: { params?: any, accounts?: any, payer?: Keypair, userSigner?: Keypair, userWritable?: boolean } = {}
+    ) {
+      const sprinkleUID = new BN(`CC${uid}`, "hex");
+      const sprinklePDA = await Sprinkle.PDA(
+        this.bakeryAuthorityKeypair.publicKey, 
+        sprinkleUID, 
+        this.program.programId
+      );
+      const sprinkleState = await this.program.account.tag.fetch(sprinklePDA);
+      const payer = options.payer ?? this.bakeryAuthorityKeypair;
 
-}
\ No newline at end of file
+      return this.program.methods
+      .claimTagV2(0, {
+        allowlistProof: [],
+        allocation: null,
+        maxPrice: null,
+        identityHash: null,
+        comboExpiresAt: null,
+        ...options.params,
+      })
+      .accounts({
+        user,
+        payer: payer.publicKey,
+        config: this.bakeryPDA,
+        tagAuthority: sprinkleAuthorityKeypair.publicKey,
+        tag: sprinklePDA,
+        userInfo: await UserInfo.PDA(
+          this.bakeryAuthorityKeypair.publicKey, 
+          sprinkleUID, 
+          user,
+          this.program.programId,
+          sprinkleState.season
+        ),
+        // Optional accounts are read by position, with the program id standing in for a missing one.
+        quest: this.program.programId,
+        questProgress: this.program.programId,
+        campaign: this.program.programId,
+        campaignUserInfo: this.program.programId,
+        sessionKey: this.program.programId,
+        treasury: this.program.programId,
+        associatedTokenProgram: this.program.programId,
+        tokenMint: this.program.programId,
+        token2022Program: this.program.programId,
+        referrer: this.program.programId,
+        referrerInfo: this.program.programId,
+        treasuryToken: this.program.programId,
+        referrerToken: this.program.programId,
+        referralInfo: this.program.programId,
+        ...options.accounts,
+      } as any)
+      .remainingAccounts([
+        ...remainingAccounts,
+        // Add the user last to force its signature, or writability for a ClaimEscrow, since anchor wont do it
+        ...(options.userSigner || options.userWritable
+          ? [{ pubkey: user, isWritable: !!options.userWritable, isSigner: !!options.userSigner }]
+          : []),
+      ])
+      .signers([payer, sprinkleAuthorityKeypair, ...(options.userSigner ? [options.userSigner] : [])])
+      .rpc()
+    }
+}
@@ -10,6 +10,7 @@ import {
   createCreateMasterEditionV3Instruction,
   createCreateMetadataAccountV3Instruction,
   createMintInstruction,
+  createVerifyCollectionInstruction,
   MasterEditionHasPrintsError,
   TokenStandard,
 } from '@metaplex-foundation/mpl-token-metadata';
@@ -52,12 +53,21 @@ export async function createRuleSetAccount(name: string, owner: Keypair, rules:
   return (await provider.sendAll([{ tx: signedTxn, signers: [owner] }]))[0];
 }
 
+export async function mintFungible(provider: Provider, payer: Keypair, owner: PublicKey, amount: number) {
+  // A plain SPL token without metadata, minted by the payer straight into the owner's ATA.
+  const tokenMint = await createMint(provider.connection, payer, payer.publicKey, payer.publicKey, 0);
+  const token = await createAssociatedTokenAccount(provider.connection, payer, tokenMint, owner);
+  await mintTo(provider.connection, payer, tokenMint, token, payer, amount);
+  return tokenMint;
+}
+
 export async function mintNFT(
   provider: Provider,
   payer: Keypair,
   creator: PublicKey,
   totalSupply: number,
-  creator2?: PublicKey
+  creator2?: PublicKey,
+  collectionMint?: PublicKey
 ) {
   // Initialize the token mint.
   const tokenMint = await createMint(provider.connection, payer, creator, creator, totalSupply);
@@ -95,7 +105,7 @@ export async function mintNFT(
               ]
             : [{ address: creator, share: 100, verified: true }],
           uses: null,
-          collection: null,
+          collection: collectionMint ? { key: collectionMint, verified: false } : null,
         },
         isMutable: true,
         collectionDetails: null,
@@ -129,6 +139,28 @@ export async function mintNFT(
   return tokenMint;
 }
 
+export async function verifyCollectionItem(
+  provider: Provider,
+  collectionAuthority: Keypair,
+  tokenMint: PublicKey,
+  collectionMint: PublicKey
+) {
+  const verifyCollectionIx = createVerifyCollectionInstruction({
+    metadata: await getMetadataPDA(tokenMint),
+    collectionAuthority: collectionAuthority.publicKey,
+    payer: collectionAuthority.publicKey,
+    collectionMint,
+    collection: await getMetadataPDA(collectionMint),
+    collectionMasterEditionAccount: await getMasterEditionPDA(collectionMint),
+  });
+
+  const txn = new Transaction().add(verifyCollectionIx);
+  txn.recentBlockhash = (await provider.connection.getRecentBlockhash()).blockhash;
+  txn.feePayer = provider.wallet.publicKey;
+  const signedTxn = await provider.wallet.signTransaction(txn);
+  return (await provider.sendAll([{ tx: signedTxn, signers: [collectionAuthority] }]))[0];
+}
+
 export async function createProgrammableNFT(
   provider: Provider,
   payer: Keypair,
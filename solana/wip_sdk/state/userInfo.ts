@@ -13,13 +13,15 @@ export class UserInfo {
 
   }
 
-  static async PDA(bakeryAuthority: PublicKey, sprinkleUID: BN, user: PublicKey, programId = CUPCAKE_PROGRAM_ID) {
+  static async PDA(bakeryAuthority: PublicKey, sprinkleUID: BN, user: PublicKey, programId = CUPCAKE_PROGRAM_ID, season = 0) {
     return (await PublicKey.findProgramAddress(
       [
         Buffer.from(PDA_PREFIX), 
         bakeryAuthority.toBuffer(), 
         sprinkleUID.toBuffer('le', 8),
-        user.toBuffer()
+        user.toBuffer(),
+        // The first season has no season seed.
+        season === 0 ? Buffer.alloc(0) : new BN(season).toBuffer('le', 4)
       ],
       programId
     ))[0]